@@ -8,12 +8,12 @@ use axum::{
     routing::get,
 };
 use serde::Deserialize;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use futures::{sink::SinkExt, stream::StreamExt};
 use shared::{
-    logic::{handle_client_event, validate_client_event},
-    traits::{Networking, ToFromBytes},
+    logic::{self, handle_client_event, validate_client_event},
+    traits::{GameSignal, Networking, ToFromBytes},
     types::{ClientEvent, CommonClientEvent, CommonServerEvent, MAX_NAME_LENGTH, ServerEvent},
 };
 use tokio::{net::TcpListener, sync::RwLock};
@@ -94,7 +94,7 @@ async fn handle_socket(socket: WebSocket, query: QueryParams, state: AppState) {
             .or_insert(types::ServerRoom::default());
         match room.handle_connection(tx, id, name) {
             Some(player_index) => {
-                let new_room = room.room.clone();
+                let new_room = logic::room_snapshot(&room.room, player_index);
                 room.connections.send_to(
                     &mut room.room,
                     ServerEvent::CommonEvent(CommonServerEvent::RoomJoined {
@@ -160,6 +160,17 @@ async fn handle_socket(socket: WebSocket, query: QueryParams, state: AppState) {
                             &mut room.connections,
                             player_index,
                         );
+                        schedule_response_timeout(&recv_state, &recv_query.code, room);
+
+                        // Logging the seed lets us replay the exact deal if a game needs debugging
+                        if let ClientEvent::CommonEvent(CommonClientEvent::StartGame) = event {
+                            println!(
+                                "({}) Started {:?} with seed {}",
+                                recv_query.code,
+                                room.room.game.value(),
+                                room.room.rng.seed()
+                            );
+                        }
                     } else {
                         println!(
                             "({}) {} sent an invalid event: {:?}",
@@ -209,6 +220,29 @@ async fn handle_socket(socket: WebSocket, query: QueryParams, state: AppState) {
     }
 }
 
+// Games like coup can give players a time limit to respond, after which the game decides for them
+fn schedule_response_timeout(state: &AppState, code: &str, room: &mut ServerRoom) {
+    let Some((window, seconds)) = logic::response_deadline(&room.room) else {
+        room.response_window = None;
+        return;
+    };
+    if room.response_window == Some(window) {
+        return; // Already waiting on this one
+    }
+    room.response_window = Some(window);
+
+    let state = state.clone();
+    let code = code.to_string();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(seconds as u64)).await;
+
+        let mut rooms = state.rooms.write().await;
+        if let Some(room) = rooms.get_mut(&code) {
+            logic::handle_response_timeout(&mut room.room, &mut room.connections, window);
+        }
+    });
+}
+
 fn is_room_empty(room: &ServerRoom) -> bool {
     room.connections.iter().all(|connection| match connection {
         Some(connection) => connection.sender.is_none(),
@@ -12,15 +12,15 @@ pub struct Connection {
 
 impl NetworkingSend for Connection {
     fn send(&mut self, event: &types::ServerEvent) {
-        if let Some(sender) = &self.sender {
-            if sender.send(event.to_bytes()).is_err() {
-                // Should force rx.recv() to return None as long as there are no other references to the sender
-                println!(
-                    "Failed to send event to player {}, closing connection",
-                    self.id
-                );
-                self.sender = None;
-            }
+        if let Some(sender) = &self.sender
+            && sender.send(event.to_bytes()).is_err()
+        {
+            // Should force rx.recv() to return None as long as there are no other references to the sender
+            println!(
+                "Failed to send event to player {}, closing connection",
+                self.id
+            );
+            self.sender = None;
         }
         // Player already disconnected
     }
@@ -30,6 +30,7 @@ impl NetworkingSend for Connection {
 pub struct ServerRoom {
     pub connections: [Option<Connection>; types::MAX_PLAYERS],
     pub room: types::Room,
+    pub response_window: Option<u16>, // The last response window a timeout was started for
 }
 
 impl ServerRoom {
@@ -64,22 +65,22 @@ impl ServerRoom {
             }
         }
 
-        if let Some(index) = first_free {
-            if let Some(name) = name {
-                self.connections[index] = Some(Connection {
-                    id,
-                    sender: Some(tx),
-                });
-                self.connections.send_to_all_except(
-                    &mut self.room,
-                    types::ServerEvent::CommonEvent(types::CommonServerEvent::PlayerJoined {
-                        name,
-                        player_index: index as u8,
-                    }),
-                    index,
-                );
-                return Some(index);
-            }
+        if let Some(index) = first_free
+            && let Some(name) = name
+        {
+            self.connections[index] = Some(Connection {
+                id,
+                sender: Some(tx),
+            });
+            self.connections.send_to_all_except(
+                &mut self.room,
+                types::ServerEvent::CommonEvent(types::CommonServerEvent::PlayerJoined {
+                    name,
+                    player_index: index as u8,
+                }),
+                index,
+            );
+            return Some(index);
         }
 
         None
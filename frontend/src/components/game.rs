@@ -1,9 +1,11 @@
+mod carbo;
 mod host_panel;
 mod other_player;
 mod player;
 mod tycoon;
 
 use crate::components::room::RoomContext;
+use carbo::Carbo;
 use host_panel::HostPanel;
 use leptos::prelude::*;
 use player::Player;
@@ -15,12 +17,13 @@ pub fn Game() -> impl IntoView {
     let mut room_context = use_context::<RoomContext>().expect("RoomContext not found");
 
     let gamemode = move || room_context.room.read().game.get();
+    let deal_verified = move || room_context.room.read().deal_verified.get();
 
     // TODO: Check if it only rerenders when the game type changes as opposed to the room changing in any way
     view! {
         <div>
             {move || match gamemode() {
-                types::GameType::Carbo => todo!("Create carbo game"),
+                types::GameType::Carbo => view! { <Carbo /> }.into_any(),
                 types::GameType::Tycoon => view! { <Tycoon /> }.into_any(),
                 types::GameType::Coup => view! {
                     <div>
@@ -30,6 +33,16 @@ pub fn Game() -> impl IntoView {
                 }.into_any(),
             }}
 
+            {move || deal_verified().map(|verified| view! {
+                <p>
+                    {if verified {
+                        "The server revealed its shuffle seed, your opening hand was dealt fairly"
+                    } else {
+                        "The revealed shuffle seed doesn't match your opening hand"
+                    }}
+                </p>
+            })}
+
             { (0..8).map(|i| view! { <Player player_index=i /> }).collect::<Vec<_>>() }
 
             <HostPanel />
@@ -0,0 +1,86 @@
+use leptos::prelude::*;
+use shared::{
+    games::carbo::CarboClientEvent,
+    types::{self, MAX_PLAYERS},
+};
+
+use crate::components::room::RoomContext;
+use player::Player;
+
+mod player;
+
+#[component]
+pub fn Carbo() -> impl IntoView {
+    let room_context = use_context::<RoomContext>().expect("RoomContext not found");
+    let room = room_context.room;
+
+    let top_card = move || {
+        room.with(|room| {
+            room.game_room
+                .carbo()
+                .map(|carbo| carbo.top_card.get())
+                .unwrap_or_default()
+        })
+    };
+    let winner = move || {
+        room.with(|room| {
+            room.game_room
+                .carbo()
+                .and_then(|carbo| carbo.winner.get())
+                .and_then(|winner| {
+                    room.players[winner as usize].get().map(|player| {
+                        String::from_utf8_lossy(&player.name.get())
+                            .trim_end_matches('\0')
+                            .to_string()
+                    })
+                })
+        })
+    };
+    let hand = move || {
+        room.with(|room| {
+            room.players[room.player_index.get() as usize]
+                .get()
+                .and_then(|player| {
+                    player
+                        .game_player
+                        .carbo()
+                        .map(|player| player.visible_cards.get())
+                })
+                .unwrap_or_default()
+        })
+    };
+
+    let send = move |event: CarboClientEvent| {
+        let mut room_context = room_context.clone();
+        room_context.send_event(types::ClientEvent::CarboEvent(event));
+    };
+    let draw = send.clone();
+
+    view! {
+        <div class="carbo">
+            {move || winner().map(|name| view! { <p>{format!("{} wins!", name)}</p> })}
+
+            <p>{move || format!("Top card: {}", top_card())}</p>
+
+            { (0..MAX_PLAYERS).map(|i| view! { <Player player_index=i /> }).collect::<Vec<_>>() }
+
+            <div class="d-flex gap-16">
+                {move || hand()
+                    .iter()
+                    .map(|card| {
+                        let send = send.clone();
+                        view! {
+                            <button on:click=move |_| send(CarboClientEvent::PlayCard { card })>
+                                {card.to_string()}
+                            </button>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </div>
+
+            <button class="btn-green" on:click=move |_| draw(CarboClientEvent::Draw)>
+                {"Draw"}
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,36 @@
+use leptos::prelude::*;
+
+use crate::components::room::RoomContext;
+
+#[component]
+pub fn Player(player_index: usize) -> impl IntoView {
+    let room_context = use_context::<RoomContext>().expect("RoomContext not found");
+    let room = room_context.room;
+
+    let num_cards = move || {
+        room.with(|room| {
+            room.players[player_index].get().and_then(|player| {
+                player
+                    .game_player
+                    .carbo()
+                    .map(|player| player.num_cards.get())
+            })
+        })
+    };
+    let is_turn = move || {
+        room.with(|room| {
+            room.game_room
+                .carbo()
+                .is_some_and(|carbo| carbo.turn.get() as usize == player_index)
+        })
+    };
+
+    view! {
+        {move || num_cards().map(|num_cards| view! {
+            <div class="carbo-player">
+                {format!("{} cards", num_cards)}
+                {move || is_turn().then_some(" (turn)")}
+            </div>
+        })}
+    }
+}
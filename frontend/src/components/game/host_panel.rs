@@ -1,4 +1,5 @@
 use leptos::prelude::*;
+use shared::types::{ClientEvent, CommonClientEvent};
 
 stylance::import_crate_style!(style, "src/components/game/host_panel.module.css");
 
@@ -6,15 +7,23 @@ use crate::components::room::RoomContext;
 
 #[component]
 pub fn HostPanel() -> impl IntoView {
-    let mut room_context = use_context::<RoomContext>().expect("RoomContext not found");
+    let room_context = use_context::<RoomContext>().expect("RoomContext not found");
+    let room = room_context.room;
 
-    let is_host = move || {
-        room_context
-            .room
-            .with(|room| room.host.get() == room.player_index.get())
+    let is_host = move || room.with(|room| room.host.get() == room.player_index.get());
+
+    let can_start = {
+        let room_context = room_context.clone();
+        move || {
+            room_context
+                .validate_client_event(&ClientEvent::CommonEvent(CommonClientEvent::StartGame))
+        }
     };
 
-    let (can_start, reason) = move || room_context.validate_client_event(ClientEve::StartGame);
+    let start_game = move |_| {
+        let mut room_context = room_context.clone();
+        room_context.send_event(ClientEvent::CommonEvent(CommonClientEvent::StartGame));
+    };
 
     view! {
         <div class={"panel ".to_owned() + style::host_panel}>
@@ -23,7 +32,8 @@ pub fn HostPanel() -> impl IntoView {
                 class="btn-green"
                 type="submit"
                 style="max-width: 100%;"
-                disabled=move || !is_host()
+                disabled=move || !is_host() || !can_start()
+                on:click=start_game
             > {"Start Game"} </button>
         </div>
     }
@@ -1,8 +1,7 @@
 //TODO: Generate this component with a proc macro
+#![allow(dead_code, unused_variables)] // Not used by any game yet, and doesn't show the player yet
 use leptos::prelude::*;
 
-use crate::components::room::RoomContext;
-
 #[component]
 pub fn OtherPlayer(player_index: usize) -> impl IntoView {
     view! {
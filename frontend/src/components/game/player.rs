@@ -1,8 +1,7 @@
 //TODO: Generate this component with a proc macro
+#![allow(unused_variables)] // Doesn't show the player yet
 use leptos::prelude::*;
 
-use crate::components::room::RoomContext;
-
 #[component]
 pub fn Player(player_index: usize) -> impl IntoView {
     view! {
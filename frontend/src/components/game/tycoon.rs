@@ -1,7 +1,6 @@
 use leptos::prelude::*;
 use shared::types::MAX_PLAYERS;
 
-use crate::components::room::RoomContext;
 use player::Player;
 
 mod player;
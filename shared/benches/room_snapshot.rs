@@ -0,0 +1,71 @@
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use shared::{
+    games::games::game_config,
+    logic,
+    traits::{GameSignal, ToFromBytes},
+    types::{CommonServerEvent, GameType, Room, ServerEvent},
+};
+
+// bincode writes an enum's variant as a u32, the old Room stored each game's state without one
+const VARIANT_TAG_SIZE: usize = 4;
+
+// A full room partway through its first deal, the largest a join message usually gets
+fn started(game: GameType) -> Room {
+    let num_players = game_config(game).max_players as usize;
+    common::started(game, num_players).0
+}
+
+fn joined(room: &Room) -> ServerEvent {
+    ServerEvent::CommonEvent(CommonServerEvent::RoomJoined {
+        new_room: logic::room_snapshot(room, 0),
+        current_player: 0,
+    })
+}
+
+// What the join message used to cost, when the room and every player also carried each inactive game's state
+fn all_games_size(room: &Room) -> usize {
+    let num_players = room
+        .players
+        .iter()
+        .filter(|player| player.value().is_some())
+        .count();
+    let inactive = GameType::ALL
+        .into_iter()
+        .filter(|game| game != room.game.value())
+        .map(|game| {
+            let room_size = game.new_room().to_bytes().len() - VARIANT_TAG_SIZE;
+            let player_size = game.new_player().to_bytes().len() - VARIANT_TAG_SIZE;
+            room_size + player_size * num_players
+        })
+        .sum::<usize>();
+
+    let active_tags = VARIANT_TAG_SIZE * (1 + num_players);
+    joined(room).to_bytes().len() - active_tags + inactive
+}
+
+fn room_snapshot(c: &mut Criterion) {
+    let mut group = c.benchmark_group("room_snapshot");
+    for game in GameType::ALL {
+        let room = started(game);
+        let event = joined(&room);
+        let size = event.to_bytes().len();
+        println!(
+            "{:?} join message: {} bytes with every game's state, {} bytes with only the active game",
+            game,
+            all_games_size(&room),
+            size
+        );
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(format!("{:?}", game), |b| {
+            b.iter(|| black_box(&event).to_bytes())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, room_snapshot);
+criterion_main!(benches);
@@ -0,0 +1,325 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign},
+    str::FromStr,
+};
+
+// Cards are stored as a bitmask where bit `rank * NUM_SUITS + suit` is a standard card and the two bits after
+// the standard cards are the jokers. Ranks are ordered by their strength in tycoon (3 is 0, 2 is 12) and suits
+// are ordered diamonds, clubs, hearts, spades, so a higher bit is always a stronger card.
+pub const NUM_SUITS: u8 = 4;
+pub const NUM_RANKS: u8 = 13;
+pub const NUM_CARDS: u8 = NUM_SUITS * NUM_RANKS + 2;
+
+pub const DIAMONDS: u8 = 0;
+pub const CLUBS: u8 = 1;
+pub const HEARTS: u8 = 2;
+pub const SPADES: u8 = 3;
+
+// Text notation used for logs and tests, a card is its rank then its suit (`3H`, `TD`, `2S`) and a joker is `JK`.
+// Sets are written as their cards separated by spaces, weakest first, e.g. `3H 3S JK`
+const RANK_CHARS: [char; NUM_RANKS as usize] = [
+    '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A', '2',
+];
+const SUIT_CHARS: [char; NUM_SUITS as usize] = ['D', 'C', 'H', 'S'];
+const JOKER: &str = "JK";
+const UNKNOWN: &str = "??"; // Only for logging sets decoded with bits past the jokers, it can't be parsed
+
+// Serialized as the plain bitmask so it's no bigger on the wire than a u64
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CardSet(u64);
+
+impl CardSet {
+    pub const EMPTY: CardSet = CardSet(0);
+    pub const JOKERS: CardSet = CardSet(0b11 << (NUM_SUITS * NUM_RANKS));
+    pub const FULL_DECK: CardSet = CardSet((1 << NUM_CARDS) - 1);
+
+    // Bits past the jokers aren't cards, so they're dropped
+    pub const fn from_bits(bits: u64) -> CardSet {
+        CardSet(bits & CardSet::FULL_DECK.0)
+    }
+
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub const fn card(rank: u8, suit: u8) -> CardSet {
+        CardSet(1 << (rank * NUM_SUITS + suit))
+    }
+
+    // Every suit of the rank
+    pub const fn all_of_rank(rank: u8) -> CardSet {
+        CardSet(0b1111 << (rank * NUM_SUITS))
+    }
+
+    // Every rank of the suit, jokers don't have a suit
+    pub const fn all_of_suit(suit: u8) -> CardSet {
+        CardSet(0x0001_1111_1111_1111 << suit)
+    }
+
+    pub const fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    // Whether every card in `other` is also in this set
+    pub const fn contains(self, other: CardSet) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn is_subset(self, other: CardSet) -> bool {
+        other.contains(self)
+    }
+
+    pub const fn intersects(self, other: CardSet) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub const fn union(self, other: CardSet) -> CardSet {
+        CardSet(self.0 | other.0)
+    }
+
+    pub const fn intersection(self, other: CardSet) -> CardSet {
+        CardSet(self.0 & other.0)
+    }
+
+    pub const fn difference(self, other: CardSet) -> CardSet {
+        CardSet(self.0 & !other.0)
+    }
+
+    pub const fn jokers(self) -> CardSet {
+        self.intersection(CardSet::JOKERS)
+    }
+
+    pub const fn standard(self) -> CardSet {
+        self.difference(CardSet::JOKERS)
+    }
+
+    pub const fn of_rank(self, rank: u8) -> CardSet {
+        self.intersection(CardSet::all_of_rank(rank))
+    }
+
+    pub const fn of_suit(self, suit: u8) -> CardSet {
+        self.intersection(CardSet::all_of_suit(suit))
+    }
+
+    // Ranks with at least one card in the set, lowest first
+    pub fn ranks(self) -> impl Iterator<Item = u8> {
+        (0..NUM_RANKS).filter(move |rank| !self.of_rank(*rank).is_empty())
+    }
+
+    // Suits with at least one card in the set, in suit order
+    pub fn suits(self) -> impl Iterator<Item = u8> {
+        (0..NUM_SUITS).filter(move |suit| !self.of_suit(*suit).is_empty())
+    }
+
+    // The standard cards grouped by rank, only ranks that are in the set are included
+    pub fn by_rank(self) -> impl Iterator<Item = (u8, CardSet)> {
+        self.ranks().map(move |rank| (rank, self.of_rank(rank)))
+    }
+
+    // The lowest rank of the run the cards make in a single suit. Jokers fill any gaps, then extend the top of the
+    // run, or the bottom once it reaches the highest rank. None if the cards can't make a run at all
+    pub fn sequence(self) -> Option<u8> {
+        let standard = self.standard();
+        if standard.suits().count() != 1 {
+            return None;
+        }
+
+        let lowest = standard.ranks().next()?;
+        let highest = standard.ranks().last()?;
+        let size = self.len();
+
+        let gaps = (highest - lowest + 1) as u32 - standard.len();
+        if gaps > self.jokers().len() || size > NUM_RANKS as u32 {
+            return None;
+        }
+
+        Some(lowest.min(NUM_RANKS - size as u8))
+    }
+
+    // Each card on its own, weakest first
+    pub fn iter(self) -> CardSetIter {
+        CardSetIter(self.0)
+    }
+}
+
+impl fmt::Display for CardSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, card) in self.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+
+            match (card.ranks().next(), card.suits().next()) {
+                (Some(rank), Some(suit)) => write!(
+                    f,
+                    "{}{}",
+                    RANK_CHARS[rank as usize], SUIT_CHARS[suit as usize]
+                )?,
+                _ if CardSet::JOKERS.contains(card) => write!(f, "{}", JOKER)?,
+                _ => write!(f, "{}", UNKNOWN)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Events and rooms are logged with {:?}, so show the cards rather than the raw bitmask
+impl fmt::Debug for CardSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CardSet({})", self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCardError {
+    InvalidCard(String),
+    DuplicateCard(String),
+    TooManyJokers,
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCardError::InvalidCard(card) => write!(f, "invalid card `{}`", card),
+            ParseCardError::DuplicateCard(card) => write!(f, "duplicate card `{}`", card),
+            ParseCardError::TooManyJokers => write!(f, "the deck only has two jokers"),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+impl FromStr for CardSet {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<CardSet, ParseCardError> {
+        let mut cards = CardSet::EMPTY;
+        for token in s.split_whitespace() {
+            if token.eq_ignore_ascii_case(JOKER) {
+                // Jokers are interchangeable, so each one takes the next free joker bit
+                let joker = (CardSet::JOKERS - cards)
+                    .iter()
+                    .next()
+                    .ok_or(ParseCardError::TooManyJokers)?;
+                cards |= joker;
+                continue;
+            }
+
+            let mut chars = token.chars().map(|c| c.to_ascii_uppercase());
+            let card = match (chars.next(), chars.next(), chars.next()) {
+                (Some(rank), Some(suit), None) => RANK_CHARS
+                    .iter()
+                    .position(|c| *c == rank)
+                    .zip(SUIT_CHARS.iter().position(|c| *c == suit))
+                    .map(|(rank, suit)| CardSet::card(rank as u8, suit as u8)),
+                _ => None,
+            }
+            .ok_or_else(|| ParseCardError::InvalidCard(token.to_string()))?;
+
+            if cards.intersects(card) {
+                return Err(ParseCardError::DuplicateCard(token.to_string()));
+            }
+            cards |= card;
+        }
+        Ok(cards)
+    }
+}
+
+pub struct CardSetIter(u64);
+
+impl Iterator for CardSetIter {
+    type Item = CardSet;
+
+    fn next(&mut self) -> Option<CardSet> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let card = self.0 & self.0.wrapping_neg();
+        self.0 &= !card;
+        Some(CardSet(card))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for CardSetIter {
+    fn next_back(&mut self) -> Option<CardSet> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let card = 1 << (63 - self.0.leading_zeros());
+        self.0 &= !card;
+        Some(CardSet(card))
+    }
+}
+
+impl ExactSizeIterator for CardSetIter {}
+
+impl IntoIterator for CardSet {
+    type Item = CardSet;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> CardSetIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<CardSet> for CardSet {
+    fn from_iter<I: IntoIterator<Item = CardSet>>(iter: I) -> CardSet {
+        iter.into_iter().fold(CardSet::EMPTY, CardSet::union)
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = CardSet;
+
+    fn bitor(self, other: CardSet) -> CardSet {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for CardSet {
+    fn bitor_assign(&mut self, other: CardSet) {
+        *self = self.union(other);
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = CardSet;
+
+    fn bitand(self, other: CardSet) -> CardSet {
+        self.intersection(other)
+    }
+}
+
+impl BitAndAssign for CardSet {
+    fn bitand_assign(&mut self, other: CardSet) {
+        *self = self.intersection(other);
+    }
+}
+
+impl Sub for CardSet {
+    type Output = CardSet;
+
+    fn sub(self, other: CardSet) -> CardSet {
+        self.difference(other)
+    }
+}
+
+impl SubAssign for CardSet {
+    fn sub_assign(&mut self, other: CardSet) {
+        *self = self.difference(other);
+    }
+}
@@ -0,0 +1,70 @@
+use sha2::{Digest, Sha256};
+
+use crate::{
+    logic,
+    shuffle::{GameRng, Seed},
+    traits::ToFromBytes,
+    types::{self, ServerEvent},
+};
+
+// Lets players check that the server didn't rig a deal. When a game starts the server broadcasts a commitment to
+// the shuffle seed and only reveals the seed once the game is over. Clients keep the room as it was before the deal
+// and every game event they were sent, so they can deal the game again from the revealed seed and make sure they
+// were sent the same opening hand. Only the opening deal is checked, anything dealt later in the game like tycoon's
+// next rounds or cards drawn in carbo and coup isn't. The seed is hashed along with a random nonce, a bare u64 seed
+// could be brute forced from its hash during the game.
+pub type Nonce = [u8; 32];
+pub type Commitment = [u8; 32];
+
+pub fn commitment(seed: Seed, nonce: &Nonce) -> Commitment {
+    let mut hasher = Sha256::new();
+    hasher.update(nonce);
+    hasher.update(seed.to_le_bytes());
+    hasher.finalize().into()
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct DealAudit {
+    commitment: Option<Commitment>,
+    room: Vec<u8>,          // The room as it was before the deal
+    received: Vec<Vec<u8>>, // Game events received since the commitment, in order
+}
+
+impl DealAudit {
+    pub fn new(commitment: Commitment, room: &types::Room) -> Self {
+        Self {
+            commitment: Some(commitment),
+            room: room.to_bytes(),
+            received: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, event: &ServerEvent) {
+        if self.commitment.is_some()
+            && !matches!(event, ServerEvent::CommonEvent(_) | ServerEvent::Unknown)
+        {
+            self.received.push(event.to_bytes());
+        }
+    }
+
+    // None if there's nothing to check, like when the player joined after the game was dealt. Only the opening deal
+    // is checked, everything sent to the player after it is ignored
+    pub fn verify(&self, seed: Seed, nonce: &Nonce, player_index: usize) -> Option<bool> {
+        if commitment(seed, nonce) != self.commitment? {
+            return Some(false);
+        }
+
+        let mut room = types::Room::from_bytes(&self.room);
+        room.rng = GameRng::new(seed, *nonce);
+
+        let dealt: Vec<Vec<u8>> = logic::deal(&mut room)
+            .into_iter()
+            .filter(|(audience, _)| audience.includes(player_index))
+            .map(|(_, event)| event.to_bytes())
+            .collect();
+        if dealt.is_empty() {
+            return None; // Not dealt in, so there's nothing to compare
+        }
+        Some(self.received.starts_with(&dealt))
+    }
+}
@@ -1,33 +1,78 @@
-use crate::{traits, types};
+use crate::{
+    cards::CardSet,
+    games::tycoon::EIGHT,
+    helpers::*,
+    outbox::{Audience, Outbox},
+    shuffle::GameRng,
+    traits::{self, GameSignal},
+    types::{self, SignalType},
+};
+use rand::seq::{IndexedRandom, SliceRandom};
 use serde::{Deserialize, Serialize};
 
+// Carbo is a shedding game played with the same 54 card deck and bitmask encoding as tycoon.
+//
+// - Everyone is dealt 7 cards, or 5 with more than 4 players, and one more card is turned up to start the
+//   discard pile (eights and jokers are skipped over so the game doesn't start on a wild card)
+// - On their turn a player either plays one card onto the discard pile or draws one card, which ends their turn
+// - A card can be played if it has the same rank or suit as the top of the discard pile
+// - Eights and jokers are wild, they can be played on anything and anything can be played on them
+// - When the draw pile runs out, everything but the top of the discard pile is shuffled into a new one. If there's
+//   nothing left to draw at all, drawing just passes the turn. The server draws a random card from the pile each
+//   time rather than keeping it in order, which amounts to the same thing
+// - The first player to get rid of all their cards wins and the room goes back to the lobby
+pub const HAND_SIZE: usize = 7;
+pub const LARGE_TABLE_HAND_SIZE: usize = 5;
+pub const LARGE_TABLE: usize = 4; // More players than this get the smaller hand
+
 #[derive(Deserialize, Serialize, Default, Clone, Copy, PartialEq, Debug)]
 pub enum CarboRoomState {
     #[default]
     Lobby,
+    Game,
 }
 
-#[derive(Clone, Copy, Deserialize, Serialize, Default, Debug)]
+#[derive(Clone, Deserialize, Serialize, Default, Debug)]
 pub struct CarboRoom {
-    pub turn: u8,
-    pub state: CarboRoomState,
+    pub turn: SignalType<u8>,
+    pub state: SignalType<CarboRoomState>,
+    pub top_card: SignalType<CardSet>,
+    pub discard_pile: CardSet, // Everything under the top card, it's all been played in the open so everyone knows it
+    pub draw_pile: Vec<CardSet>, // Only the server knows what's left to draw
+    pub winner: SignalType<Option<u8>>,
 }
 
-#[derive(Clone, Copy, Deserialize, Serialize, Default, Debug)]
+#[derive(Clone, Deserialize, Serialize, Default, Debug)]
 pub struct CarboPlayer {
-    pub visible_cards: u64,
-    pub num_cards: u8,
+    pub visible_cards: SignalType<CardSet>, // The player's hand, only they (and the server) can see it
+    pub num_cards: SignalType<u8>,
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize, Debug)]
 pub enum CarboServerEvent {
-    GameStarted { turn: u8, cards: u64 },
+    // Only has the player's own hand, everyone is dealt the same number of cards
+    GameStarted {
+        turn: u8,
+        cards: CardSet,
+        top_card: CardSet,
+    },
+    CardPlayed {
+        card: CardSet,
+    },
+    // Everyone is told that the current player drew, only they are sent the card with CardReceived
+    CardDrawn {
+        drew: bool,
+        reshuffled: bool,
+    },
+    CardReceived {
+        card: CardSet,
+    },
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize, Debug)]
 pub enum CarboClientEvent {
-    StartGame,
-    PlayCard { card: u64 },
+    PlayCard { card: CardSet },
+    Draw,
 }
 
 impl traits::GameLogic for CarboRoom {
@@ -36,35 +81,79 @@ impl traits::GameLogic for CarboRoom {
     type Room = CarboRoom;
     type Player = CarboPlayer;
 
+    const MIN_PLAYERS: u16 = 3;
+    const MAX_PLAYERS: u16 = 8;
+
     fn validate_client_game_event(
         room: &types::Room,
         event: &CarboClientEvent,
         player_index: usize,
     ) -> bool {
+        if *room.carbo().state.value() != CarboRoomState::Game
+            || *room.carbo().turn.value() != player_index as u8
+        {
+            return false;
+        }
+
         match event {
-            CarboClientEvent::StartGame => room.carbo.state == CarboRoomState::Lobby,
             CarboClientEvent::PlayCard { card } => {
-                todo!("Validate card played");
+                let Some(player) = get_player(room, player_index) else {
+                    return false;
+                };
+
+                card.len() == 1
+                    && player.carbo().visible_cards.value().contains(*card)
+                    && can_play(*card, *room.carbo().top_card.value())
             }
+            CarboClientEvent::Draw => true,
         }
     }
 
     fn handle_client_game_event(
-        room: &mut types::Room,
+        room: &types::Room,
         event: &Self::GameClientEvent,
-        connections: &mut impl traits::Networking,
         player_index: usize,
-    ) {
-        if Self::validate_client_game_event(room, event, player_index) {
-            match event {
-                CarboClientEvent::StartGame => {
-                    todo!("Handle game started");
-                }
-                CarboClientEvent::PlayCard { card } => {
-                    todo!("Handle card played");
+        rng: &mut GameRng,
+    ) -> Vec<(Audience, types::ServerEvent)> {
+        let mut outbox = Outbox::new(room);
+
+        match event {
+            CarboClientEvent::PlayCard { card } => {
+                outbox.send_to_all_except_origin::<Self>(
+                    CarboServerEvent::CardPlayed { card: *card },
+                    player_index,
+                );
+            }
+            CarboClientEvent::Draw => {
+                let reshuffled =
+                    room.carbo().draw_pile.is_empty() && !room.carbo().discard_pile.is_empty();
+                let card = if reshuffled {
+                    room.carbo()
+                        .discard_pile
+                        .iter()
+                        .collect::<Vec<_>>()
+                        .choose(rng)
+                        .copied()
+                } else {
+                    room.carbo().draw_pile.choose(rng).copied()
+                };
+
+                outbox.send_to_all::<Self>(CarboServerEvent::CardDrawn {
+                    drew: card.is_some(),
+                    reshuffled,
+                });
+
+                if let Some(card) = card {
+                    outbox.send_to::<Self>(CarboServerEvent::CardReceived { card }, player_index);
                 }
             }
         }
+
+        outbox.into_events()
+    }
+
+    fn is_server_only(event: &CarboClientEvent) -> bool {
+        matches!(event, CarboClientEvent::Draw)
     }
 
     fn handle_server_game_event(
@@ -74,21 +163,168 @@ impl traits::GameLogic for CarboRoom {
         is_server_side: bool,
     ) {
         match event {
-            CarboServerEvent::GameStarted { turn, cards } => {
-                todo!("Handle game started");
+            CarboServerEvent::GameStarted {
+                turn,
+                cards,
+                top_card,
+            } => {
+                room.state.set(types::RoomState::InGame);
+                room.carbo_mut().state.set(CarboRoomState::Game);
+                room.carbo_mut().turn.set(*turn);
+                room.carbo_mut().top_card.set(*top_card);
+                room.carbo_mut().discard_pile = CardSet::EMPTY;
+                room.carbo_mut().winner.set(None);
+
+                // Each player is only sent their own hand
+                for (index, player) in room.players.iter_mut().enumerate() {
+                    if let Some(player) = player.value_mut() {
+                        player.carbo_mut().num_cards.set(cards.len() as u8);
+
+                        if player_index == Some(index) {
+                            player.carbo_mut().visible_cards.set(*cards);
+                        } else if !is_server_side {
+                            player.carbo_mut().visible_cards.set(CardSet::EMPTY);
+                        }
+                    }
+                }
+            }
+            CarboServerEvent::CardPlayed { card } => {
+                let turn = *room.carbo().turn.value() as usize;
+                let Some(player) = get_player_mut(room, turn) else {
+                    return;
+                };
+
+                let player = player.carbo_mut();
+                player
+                    .visible_cards
+                    .set(*player.visible_cards.value() - *card);
+                let num_cards = player.num_cards.value().saturating_sub(1);
+                player.num_cards.set(num_cards);
+
+                let carbo = room.carbo_mut();
+                carbo.discard_pile |= *carbo.top_card.value();
+                carbo.top_card.set(*card);
+
+                if num_cards == 0 {
+                    room.carbo_mut().winner.set(Some(turn as u8));
+                    room.carbo_mut().state.set(CarboRoomState::Lobby);
+                    room.state.set(types::RoomState::Lobby);
+                } else {
+                    next_turn(room);
+                }
+            }
+            CarboServerEvent::CardDrawn { drew, reshuffled } => {
+                if *reshuffled {
+                    if is_server_side {
+                        room.carbo_mut().draw_pile = room.carbo().discard_pile.iter().collect();
+                    }
+                    room.carbo_mut().discard_pile = CardSet::EMPTY;
+                }
+
+                let turn = *room.carbo().turn.value() as usize;
+                if *drew && let Some(player) = get_player_mut(room, turn) {
+                    let player = player.carbo_mut();
+                    player.num_cards.set(player.num_cards.value() + 1);
+                }
+
+                next_turn(room);
+            }
+            CarboServerEvent::CardReceived { card } => {
+                if is_server_side {
+                    room.carbo_mut().draw_pile.retain(|drawn| drawn != card);
+                }
+
+                if let Some(player) = player_index.and_then(|index| get_player_mut(room, index)) {
+                    let player = player.carbo_mut();
+                    player
+                        .visible_cards
+                        .set(*player.visible_cards.value() | *card);
+                }
             }
         }
     }
 
-    fn validate_start_game(room: &types::Room, player_index: usize) -> bool {
-        room.carbo.state == CarboRoomState::Lobby
+    fn validate_start_game(room: &types::Room, _: usize) -> bool {
+        *room.carbo().state.value() == CarboRoomState::Lobby
     }
 
-    fn handle_start_game(room: &mut types::Room, connections: &mut impl traits::Networking) {
-        todo!("Handle game started");
+    fn handle_start_game(room: &mut types::Room) -> Vec<(Audience, types::ServerEvent)> {
+        let seats: Vec<usize> = seated_players(room).collect();
+
+        let mut deck: Vec<CardSet> = CardSet::FULL_DECK.iter().collect();
+        deck.shuffle(&mut room.rng);
+
+        let Some(turn) = seats.choose(&mut room.rng).copied() else {
+            return Vec::new();
+        };
+
+        let hand_size = if seats.len() > LARGE_TABLE {
+            LARGE_TABLE_HAND_SIZE
+        } else {
+            HAND_SIZE
+        };
+        let hands: Vec<(usize, CardSet)> = seats
+            .iter()
+            .map(|seat| {
+                let cards = (0..hand_size).filter_map(|_| deck.pop()).collect();
+                (*seat, cards)
+            })
+            .collect();
+
+        let top_card = match deck.iter().position(|card| !is_wild(*card)) {
+            Some(index) => deck.remove(index),
+            None => deck.pop().unwrap_or_default(),
+        };
+        room.carbo_mut().draw_pile = deck;
+
+        hands
+            .into_iter()
+            .map(|(seat, cards)| {
+                let event = CarboServerEvent::GameStarted {
+                    turn: turn as u8,
+                    cards,
+                    top_card,
+                };
+                (Audience::Player(seat), Self::wrap_game_event(event))
+            })
+            .collect()
+    }
+
+    fn hide_private_state(room: &mut types::Room, player_index: usize) {
+        room.carbo_mut().draw_pile.clear();
+
+        for (index, player) in room.players.iter_mut().enumerate() {
+            if index != player_index
+                && let Some(player) = player.value_mut()
+            {
+                player.carbo_mut().visible_cards.set(CardSet::EMPTY);
+            }
+        }
     }
 
     fn wrap_game_event(event: Self::GameServerEvent) -> types::ServerEvent {
         types::ServerEvent::CarboEvent(event)
     }
 }
+
+pub const WILD_CARDS: CardSet = CardSet::JOKERS.union(CardSet::all_of_rank(EIGHT));
+
+pub fn can_play(card: CardSet, top_card: CardSet) -> bool {
+    if is_wild(card) || is_wild(top_card) {
+        return true;
+    }
+
+    card.ranks().next() == top_card.ranks().next() || card.suits().next() == top_card.suits().next()
+}
+
+pub fn is_wild(card: CardSet) -> bool {
+    card.intersects(WILD_CARDS)
+}
+
+fn next_turn(room: &mut types::Room) {
+    let turn = *room.carbo().turn.value() as usize;
+    let seats: Vec<usize> = seated_players(room).collect();
+    if let Some(next) = seats.iter().find(|seat| **seat > turn).or(seats.first()) {
+        room.carbo_mut().turn.set(*next as u8);
+    }
+}
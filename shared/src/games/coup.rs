@@ -1,10 +1,31 @@
 use crate::{
     helpers::*,
+    outbox::{Audience, Outbox},
+    shuffle::GameRng,
     traits::{self, GameSignal},
-    types::{self, SignalType},
+    types::{self, MAX_PLAYERS, SignalType},
 };
+use rand::seq::{IndexedRandom, SliceRandom};
 use serde::{Deserialize, Serialize};
 
+pub const COURT_ROLES: [Role; 5] = [
+    Role::Duke,
+    Role::Assassin,
+    Role::Captain,
+    Role::Ambassador,
+    Role::Contessa,
+];
+pub const CARDS_PER_ROLE: usize = 3;
+pub const STARTING_COINS: u8 = 2;
+pub const ASSASSINATE_COST: u8 = 3;
+pub const COUP_COST: u8 = 7;
+pub const MUST_COUP_COINS: u8 = 10;
+pub const STEAL_AMOUNT: u8 = 2;
+pub const EXCHANGE_DRAW: usize = 2;
+pub const INQUISITOR_EXCHANGE_DRAW: usize = 1;
+pub const CONVERT_SELF_COST: u8 = 1;
+pub const CONVERT_OTHER_COST: u8 = 2;
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Role {
     Duke,
@@ -12,11 +33,35 @@ pub enum Role {
     Captain,
     Ambassador,
     Contessa,
+    Inquisitor, // Replaces the ambassador when the variant is on
 
     #[default]
     Unknown,
 }
 
+// How a player answered a claim while everyone had the chance to stop it. Challenges are tracked on their own,
+// since a player who loses a challenge can still block the action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Response {
+    Allow,
+    Block,
+}
+
+// Reformation splits the table into two factions that can't act against each other
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Allegiance {
+    #[default]
+    Loyalist,
+    Reformist,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoupSettings {
+    pub response_timeout: Option<u8>, // Seconds before anyone who hasn't responded is assumed to allow the action
+    pub reformation: bool,
+    pub inquisitor: bool,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Card {
     pub role: SignalType<Role>,
@@ -27,37 +72,111 @@ pub struct Card {
 #[derive(Clone, Deserialize, Serialize, Default, Debug)]
 pub struct CoupRoom {
     pub turn: SignalType<u8>,
-    pub deck: [Card; 15],
+    pub deck: Vec<Role>, // The court deck, only the server knows what's left in it
     pub last_action: SignalType<Option<PlayerAction>>,
-    pub last_counteraction: Option<Role>,
-    pub challenge: SignalType<Option<(u8, u8)>>, // (player, challenger)
+    pub last_counteraction: SignalType<Option<PlayerAction>>, // A block of the last action, waiting to be challenged
+    pub challenge: SignalType<Option<(u8, u8)>>,              // (player, challenger)
+    pub influence_loss: SignalType<Vec<u8>>, // Players that still need to choose a card to lose, in order
+    pub exchange: SignalType<Option<u8>>,    // Player in the middle of an exchange
+    pub exchange_cards: Vec<Role>, // Cards drawn for the exchange, only the server and the exchanging player know them
+    pub responses: SignalType<[Option<Response>; MAX_PLAYERS]>, // Responses to the pending action, by player
+    pub challenged: SignalType<bool>, // Whether the claim on top has been challenged, it can only happen once
+    pub response_window: u16, // Counts up each time a new action is opened to responses, so a timeout can tell if it's stale
+    pub settings: SignalType<CoupSettings>,
+    pub winner: SignalType<Option<u8>>, // Last player standing from the previous game
+    pub treasury: SignalType<u8>,       // Reformation's treasury reserve, filled by conversions
+    pub examine: SignalType<Option<(u8, u8)>>, // (inquisitor, target) while an examine is being carried out
+    pub examined_card: SignalType<Option<u8>>, // The card the target showed, only the inquisitor knows its role
 }
 
 #[derive(Clone, Deserialize, Serialize, Default, Debug)]
 pub struct CoupPlayer {
     pub coins: SignalType<u8>,
     pub cards: [Card; 2],
+    pub allegiance: SignalType<Allegiance>,
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
 pub enum CoupServerEvent {
-    GameStarted { turn: u8, cards: [Card; 2] },
-    Action { player: u8, action: PlayerAction },
-    Counteraction { player: u8, claim: Role },
-    Challenge { player: u8 },
-    CardRevealed { player: u8, card: u8 }, // 0 or 1
+    // Only has the player's own roles, everyone else's are unknown
+    GameStarted {
+        turn: u8,
+        cards: [Role; 2],
+    },
+    Action {
+        player: u8,
+        action: PlayerActionType,
+    },
+    Counteraction {
+        player: u8,
+        claim: Role,
+    },
+    Challenge {
+        player: u8,
+    },
+    // A player losing influence turns over the card of their choice
+    CardRevealed {
+        player: u8,
+        card: u8, // 0 or 1
+        role: Role,
+    },
     // Counters last action
-    // Person who was challenged reveals a card, clients can work out if they were correct
+    // Person who was challenged reveals a card, the server says whether it proved their claim since an embezzle
+    // claims to not have the duke, which one card can't show
     // I would like to allow the person being challenge to lie about not having the card
     // which is why the person being challenge should be able to choose which card to reveal
     // regardless of the wether they have the correct card or not
-    ChallengeRevealed { player: u8, card: u8 },
+    ChallengeRevealed {
+        player: u8,
+        card: u8,
+        role: Role,
+        proven: bool,
+    },
+    // Sent privately to a player that proved a challenge, their revealed card is shuffled back into the deck and
+    // this is the card they drew to replace it
+    CardReplaced {
+        card: u8,
+        role: Role,
+    },
+    // The exchange is done in private between the server and the exchanging player, everyone else only finds out
+    // once it's finished
+    ExchangeDrawn {
+        cards: Vec<Role>,
+    },
+    ExchangeKept {
+        cards: [Role; 2],
+    },
+    ExchangeFinished {
+        player: u8,
+    },
+    // The target of an examine shows a card to the inquisitor, everyone knows which card but only the inquisitor
+    // gets sent the role
+    CardShown {
+        card: u8,
+    },
+    CardExamined {
+        card: u8,
+        role: Role,
+    },
+    // If the inquisitor made them swap it, the target is privately sent their new card with CardReplaced
+    ExamineFinished {
+        swapped: bool,
+    },
+    Allowed {
+        player: u8,
+    },
+    SettingsChanged {
+        settings: CoupSettings,
+    },
+    GameOver {
+        winner: u8,
+    },
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize, Debug)]
 pub struct PlayerAction {
-    action: ActionType,
-    player: u8,
+    pub action: ActionType,
+    pub player: u8,
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize, Debug)]
@@ -70,20 +189,28 @@ pub enum ActionType {
 pub enum PlayerActionType {
     Income,                     // Gain 1 coin
     ForeignAid,                 // Gain 2 coins
-    Coup,                       // Pay 7 coins to eliminate a player
+    Coup { target: u8 },        // Pay 7 coins to eliminate a player
     Tax,                        // Duke
     Assassinate { target: u8 }, // Assassin
     Exchange,                   // Ambassador
     Steal { target: u8 },       // Captain
+    Examine { target: u8 }, // Inquisitor, look at one of the target's cards and choose to make them swap it
+    Convert { target: u8 }, // Reformation, pay into the treasury to switch someone's allegiance, including your own
+    Embezzle,               // Reformation, take the treasury by claiming to not have the duke
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize, Debug)]
 pub enum CoupClientEvent {
-    Action { action: PlayerAction },
+    Action { action: PlayerActionType },
     Counteraction { claim: Role },
     Challenge,
     ResolveChallenge { card: u8 },
     RevealCard { card: u8 },
+    ChooseExchange { keep: u8 }, // Bits 0 and 1 are the player's cards, the rest are the drawn cards
+    ShowCard { card: u8 },
+    ForceSwap { swap: bool },
+    Allow,
+    SetSettings { settings: CoupSettings },
 }
 
 impl traits::GameLogic for CoupRoom {
@@ -92,101 +219,132 @@ impl traits::GameLogic for CoupRoom {
     type Room = CoupRoom;
     type Player = CoupPlayer;
 
+    const MIN_PLAYERS: u16 = 3;
+    const MAX_PLAYERS: u16 = 6;
+
     fn validate_client_game_event(
         room: &types::Room,
         event: &Self::GameClientEvent,
         player_index: usize,
     ) -> bool {
-        if !is_player_alive(room, player_index) {
+        if let CoupClientEvent::SetSettings { settings: _ } = event {
+            return is_host(room, player_index) && is_lobby(room);
+        }
+
+        if is_lobby(room) || !is_player_alive(room, player_index) {
             return false;
         }
 
         match event {
-            CoupClientEvent::Action { action: _ } => {
-                *room.coup.turn.value() == player_index as u8 && !has_unresolved_challenge(room)
-            }
-            CoupClientEvent::Counteraction { claim } => {
-                if has_unresolved_challenge(room) {
+            CoupClientEvent::Action { action } => {
+                if *room.coup().turn.value() != player_index as u8
+                    || room.coup().last_action.value().is_some()
+                    || has_unresolved_challenge(room)
+                    || !room.coup().influence_loss.value().is_empty()
+                    || room.coup().exchange.value().is_some()
+                    || room.coup().examine.value().is_some()
+                {
                     return false;
                 }
 
-                let last_action = match room.coup.last_action.value() {
-                    Some(action) => action,
-                    None => return false,
-                };
-
-                if last_action.player == player_index as u8 {
-                    return false; // Can't counter if the last action was taken by us
+                let coins = coins(room, player_index);
+                if coins >= MUST_COUP_COINS && !matches!(action, PlayerActionType::Coup { .. }) {
+                    return false;
                 }
 
-                let last_action = match last_action.action {
-                    ActionType::Action(action) => action,
-                    _ => return false,
-                };
-
-                // Check if the action is counterable and if the claim is correct
-                match last_action {
-                    PlayerActionType::ForeignAid => *claim == Role::Duke,
-                    PlayerActionType::Steal { target } => {
-                        (*claim == Role::Captain || *claim == Role::Ambassador)
-                            && target == player_index as u8
+                let reformation = room.coup().settings.value().reformation;
+                match action {
+                    PlayerActionType::Coup { target } => {
+                        coins >= COUP_COST && can_target(room, *target, player_index)
                     }
                     PlayerActionType::Assassinate { target } => {
-                        *claim == Role::Contessa && target == player_index as u8
+                        coins >= ASSASSINATE_COST && can_target(room, *target, player_index)
+                    }
+                    PlayerActionType::Steal { target } => can_target(room, *target, player_index),
+                    PlayerActionType::Convert { target } => {
+                        reformation
+                            && is_player_alive(room, *target as usize)
+                            && coins >= convert_cost(*target, player_index)
+                    }
+                    PlayerActionType::Embezzle => reformation,
+                    PlayerActionType::Examine { target } => {
+                        room.coup().settings.value().inquisitor
+                            && can_target(room, *target, player_index)
                     }
-                    _ => false,
+                    PlayerActionType::Income
+                    | PlayerActionType::ForeignAid
+                    | PlayerActionType::Tax
+                    | PlayerActionType::Exchange => true,
                 }
             }
-
+            CoupClientEvent::Counteraction { claim } => {
+                can_respond(room, player_index) && can_block(room, player_index, *claim)
+            }
             CoupClientEvent::Challenge => {
-                if has_unresolved_challenge(room) {
+                can_respond(room, player_index) && can_challenge(room, player_index)
+            }
+            CoupClientEvent::Allow => can_respond(room, player_index),
+            CoupClientEvent::SetSettings { settings: _ } => false, // Only allowed in the lobby, checked above
+            CoupClientEvent::RevealCard { card } => {
+                // Challenges are settled first, since they can change who has to lose influence
+                room.coup().influence_loss.value().first() == Some(&(player_index as u8))
+                    && !has_unresolved_challenge(room)
+                    && is_unrevealed(room, player_index, *card)
+            }
+            CoupClientEvent::ChooseExchange { keep } => {
+                if *room.coup().exchange.value() != Some(player_index as u8)
+                    || room.coup().exchange_cards.is_empty()
+                {
                     return false;
                 }
 
-                // Check if the last action was not taken by us and is challengeable by us
-                let last_action = match room.coup.last_action.value() {
-                    Some(action) => action,
-                    None => return false,
-                };
-
-                if last_action.player == player_index as u8 {
-                    return false; // Can't challenge if the last action was taken by us
-                };
+                // Has to keep as many cards as they have influence, and can't take back a revealed card
+                let unrevealed = (0..2)
+                    .filter(|card| is_unrevealed(room, player_index, *card))
+                    .fold(0u8, |mask, card| mask | 1 << card);
+                let drawn = ((1u8 << room.coup().exchange_cards.len()) - 1) << 2;
+                let allowed = unrevealed | drawn;
 
-                match last_action.action {
-                    ActionType::Action(action) => match action {
-                        PlayerActionType::Exchange | PlayerActionType::Tax => true,
-                        PlayerActionType::Assassinate { target }
-                        | PlayerActionType::Steal { target } => target == player_index as u8,
-                        _ => false,
-                    },
-                    // If its a counteraction, we can only challenge if the counteraction is against us
-                    ActionType::Counteraction { claim: _, against } => {
-                        against == player_index as u8
-                    }
-                }
-            }
-            CoupClientEvent::RevealCard { card: _ } => {
-                todo!("Validate reveal card");
+                keep & !allowed == 0 && keep.count_ones() == unrevealed.count_ones()
             }
+            CoupClientEvent::ShowCard { card } => match room.coup().examine.value() {
+                Some((_, target)) => {
+                    *target == player_index as u8
+                        && room.coup().examined_card.value().is_none()
+                        && room.coup().influence_loss.value().is_empty()
+                        && is_unrevealed(room, player_index, *card)
+                }
+                None => false,
+            },
+            CoupClientEvent::ForceSwap { swap: _ } => match room.coup().examine.value() {
+                Some((inquisitor, _)) => {
+                    *inquisitor == player_index as u8 && room.coup().examined_card.value().is_some()
+                }
+                None => false,
+            },
             CoupClientEvent::ResolveChallenge { card } => {
-                // If we were the player that took the last action and there is a challenge
-                // check if we have the card we are revealing
-                todo!("Validate resolve challenge");
+                // Any unrevealed card can be shown, whether or not it's the one that was claimed
+                match room.coup().challenge.value() {
+                    Some((player, _)) => {
+                        *player == player_index as u8 && is_unrevealed(room, player_index, *card)
+                    }
+                    None => false,
+                }
             }
         }
     }
 
     fn handle_client_game_event(
-        room: &mut types::Room,
+        room: &types::Room,
         event: &Self::GameClientEvent,
-        connections: &mut impl traits::Networking,
         player_index: usize,
-    ) {
+        rng: &mut GameRng,
+    ) -> Vec<(Audience, types::ServerEvent)> {
+        let mut outbox = Outbox::new(room);
+
         match event {
             CoupClientEvent::Action { action } => {
-                connections.send_to_all_except_origin_game_event::<Self>(
-                    room,
+                outbox.send_to_all_except_origin::<Self>(
                     CoupServerEvent::Action {
                         player: player_index as u8,
                         action: *action,
@@ -195,8 +353,7 @@ impl traits::GameLogic for CoupRoom {
                 );
             }
             CoupClientEvent::Counteraction { claim } => {
-                connections.send_to_all_except_origin_game_event::<Self>(
-                    room,
+                outbox.send_to_all_except_origin::<Self>(
                     CoupServerEvent::Counteraction {
                         player: player_index as u8,
                         claim: *claim,
@@ -205,21 +362,136 @@ impl traits::GameLogic for CoupRoom {
                 );
             }
             CoupClientEvent::Challenge => {
-                connections.send_to_all_except_origin_game_event::<Self>(
-                    room,
+                outbox.send_to_all_except_origin::<Self>(
                     CoupServerEvent::Challenge {
                         player: player_index as u8,
                     },
                     player_index,
                 );
             }
+            CoupClientEvent::Allow => {
+                outbox.send_to_all_except_origin::<Self>(
+                    CoupServerEvent::Allowed {
+                        player: player_index as u8,
+                    },
+                    player_index,
+                );
+            }
+            CoupClientEvent::SetSettings { settings } => {
+                outbox.send_to_all_except_origin::<Self>(
+                    CoupServerEvent::SettingsChanged {
+                        settings: *settings,
+                    },
+                    player_index,
+                );
+            }
             CoupClientEvent::RevealCard { card } => {
-                todo!("Handle reveal card");
+                let role = get_player(room, player_index)
+                    .map(|player| *player.coup().cards[*card as usize].role.value())
+                    .unwrap_or_default();
+
+                outbox.send_to_all::<Self>(CoupServerEvent::CardRevealed {
+                    player: player_index as u8,
+                    card: *card,
+                    role,
+                });
             }
             CoupClientEvent::ResolveChallenge { card } => {
-                todo!("Handle resolve challenge");
+                let role = get_player(room, player_index)
+                    .map(|player| *player.coup().cards[*card as usize].role.value())
+                    .unwrap_or_default();
+                let proven = match pending_claim(room) {
+                    Some(PlayerAction {
+                        action: ActionType::Action(PlayerActionType::Embezzle),
+                        player: _,
+                    }) => !has_role(room, player_index, Role::Duke),
+                    claim => claim.and_then(|claim| claimed_role(room, &claim)) == Some(role),
+                };
+
+                outbox.send_to_all::<Self>(CoupServerEvent::ChallengeRevealed {
+                    player: player_index as u8,
+                    card: *card,
+                    role,
+                    proven,
+                });
+
+                // The proven card is back in the deck by now, so it could be drawn again
+                if proven {
+                    let role = draw_card(outbox.room(), rng);
+                    outbox.send_to::<Self>(
+                        CoupServerEvent::CardReplaced { card: *card, role },
+                        player_index,
+                    );
+                }
+            }
+            CoupClientEvent::ChooseExchange { keep } => {
+                let Some(player) = get_player(room, player_index) else {
+                    return Vec::new();
+                };
+
+                let mut options: Vec<Role> = player
+                    .coup()
+                    .cards
+                    .iter()
+                    .map(|card| *card.role.value())
+                    .collect();
+                options.extend(room.coup().exchange_cards.iter());
+
+                // Kept cards fill the unrevealed slots in order, everything else goes back into the deck
+                let mut kept = options
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| keep & (1 << index) != 0)
+                    .map(|(_, role)| *role);
+                let mut cards = [options[0], options[1]];
+                for (index, card) in cards.iter_mut().enumerate() {
+                    if is_unrevealed(room, player_index, index as u8) {
+                        *card = kept.next().unwrap_or_default();
+                    }
+                }
+
+                outbox.send_to::<Self>(CoupServerEvent::ExchangeKept { cards }, player_index);
+                outbox.send_to_all::<Self>(CoupServerEvent::ExchangeFinished {
+                    player: player_index as u8,
+                });
+            }
+            CoupClientEvent::ShowCard { card } => {
+                let Some((inquisitor, _)) = *room.coup().examine.value() else {
+                    return Vec::new();
+                };
+                let role = get_player(room, player_index)
+                    .map(|player| *player.coup().cards[*card as usize].role.value())
+                    .unwrap_or_default();
+
+                outbox.send_to_all::<Self>(CoupServerEvent::CardShown { card: *card });
+                outbox.send_to::<Self>(
+                    CoupServerEvent::CardExamined { card: *card, role },
+                    inquisitor as usize,
+                );
+            }
+            CoupClientEvent::ForceSwap { swap } => {
+                let Some((_, target)) = *room.coup().examine.value() else {
+                    return Vec::new();
+                };
+                let Some(card) = *room.coup().examined_card.value() else {
+                    return Vec::new();
+                };
+
+                outbox.send_to_all::<Self>(CoupServerEvent::ExamineFinished { swapped: *swap });
+
+                if *swap {
+                    let role = draw_card(outbox.room(), rng);
+                    outbox.send_to::<Self>(
+                        CoupServerEvent::CardReplaced { card, role },
+                        target as usize,
+                    );
+                }
             }
         }
+
+        draw_exchange_cards(&mut outbox, rng);
+        check_game_over(&mut outbox);
+        outbox.into_events()
     }
 
     fn handle_server_game_event(
@@ -230,32 +502,427 @@ impl traits::GameLogic for CoupRoom {
     ) {
         match event {
             CoupServerEvent::GameStarted { turn, cards } => {
-                todo!("Handle game started");
+                room.state.set(types::RoomState::InGame);
+                room.coup_mut().turn.set(*turn);
+                room.coup_mut().last_action.set(None);
+                room.coup_mut().last_counteraction.set(None);
+                room.coup_mut().winner.set(None);
+                room.coup_mut().challenge.set(None);
+                room.coup_mut().influence_loss.set(Vec::new());
+                room.coup_mut().exchange.set(None);
+                room.coup_mut().exchange_cards.clear();
+                room.coup_mut().responses.set(Default::default());
+                room.coup_mut().challenged.set(false);
+                room.coup_mut().treasury.set(0);
+                room.coup_mut().examine.set(None);
+                room.coup_mut().examined_card.set(None);
+
+                // Factions alternate around the table
+                let reformation = room.coup().settings.value().reformation;
+                let seats: Vec<usize> = seated_players(room).collect();
+                for (seat, index) in seats.into_iter().enumerate() {
+                    if let Some(player) = get_player_mut(room, index) {
+                        player
+                            .coup_mut()
+                            .allegiance
+                            .set(if reformation && seat % 2 == 1 {
+                                Allegiance::Reformist
+                            } else {
+                                Allegiance::Loyalist
+                            });
+                    }
+                }
+
+                // Each player is only sent their own roles
+                for (index, player) in room.players.iter_mut().enumerate() {
+                    if let Some(player) = player.value_mut() {
+                        player.coup_mut().coins.set(STARTING_COINS);
+
+                        for (card, role) in player.coup_mut().cards.iter_mut().zip(cards) {
+                            card.revealed.set(false);
+
+                            if player_index == Some(index) {
+                                card.role.set(*role);
+                            } else if !is_server_side {
+                                card.role.set(Role::Unknown);
+                            }
+                        }
+                    }
+                }
             }
             CoupServerEvent::Action { player, action } => {
-                todo!("Handle action taken");
+                // Costs are paid up front, so they're lost even if the action ends up being blocked
+                let cost = match action {
+                    PlayerActionType::Coup { target: _ } => COUP_COST,
+                    PlayerActionType::Assassinate { target: _ } => ASSASSINATE_COST,
+                    PlayerActionType::Convert { target } => {
+                        let cost = convert_cost(*target, *player as usize);
+                        let treasury = &mut room.coup_mut().treasury;
+                        treasury.set(treasury.value().saturating_add(cost));
+                        cost
+                    }
+                    _ => 0,
+                };
+                add_coins(room, *player as usize, -(cost as i16));
+
+                room.coup_mut().last_action.set(Some(PlayerAction {
+                    action: ActionType::Action(*action),
+                    player: *player,
+                }));
+                room.coup_mut().responses.set(Default::default());
+                room.coup_mut().challenged.set(false);
+                room.coup_mut().response_window = room.coup().response_window.wrapping_add(1);
+
+                // Nobody can stop income or a coup, so there's nobody to wait on
+                if everyone_responded(room) {
+                    resolve_action(room);
+                }
             }
             CoupServerEvent::Counteraction { player, claim } => {
-                todo!("Handle counteraction");
+                let Some(action) = *room.coup().last_action.value() else {
+                    return;
+                };
+
+                // The block is a claim of its own, so everyone gets a fresh chance to challenge it
+                room.coup_mut().last_counteraction.set(Some(PlayerAction {
+                    action: ActionType::Counteraction {
+                        claim: *claim,
+                        against: action.player,
+                    },
+                    player: *player,
+                }));
+                room.coup_mut().responses.set(Default::default());
+                room.coup_mut().challenged.set(false);
+                room.coup_mut().response_window = room.coup().response_window.wrapping_add(1);
+                record_response(room, *player as usize, Response::Block);
             }
             CoupServerEvent::Challenge { player } => {
-                todo!("Handle challenge");
+                if let Some(claim) = pending_claim(room) {
+                    room.coup_mut().challenge.set(Some((claim.player, *player)));
+                }
+                room.coup_mut().challenged.set(true);
+            }
+            CoupServerEvent::CardRevealed { player, card, role } => {
+                if let Some(card) = get_player_mut(room, *player as usize)
+                    .and_then(|player| player.coup_mut().cards.get_mut(*card as usize))
+                {
+                    card.role.set(*role);
+                    card.revealed.set(true);
+                }
+
+                // Anyone else who was knocked out in the meantime has nothing left to lose
+                let mut influence_loss = room.coup().influence_loss.value().clone();
+                if influence_loss.first() == Some(player) {
+                    influence_loss.remove(0);
+                }
+                influence_loss.retain(|player| is_player_alive(room, *player as usize));
+                room.coup_mut().influence_loss.set(influence_loss);
+
+                // There's nothing left to examine if the target was knocked out
+                if let Some((_, target)) = *room.coup().examine.value()
+                    && !is_player_alive(room, target as usize)
+                {
+                    room.coup_mut().examine.set(None);
+                    room.coup_mut().examined_card.set(None);
+                }
+
+                // Knocking out the last player who could still respond leaves nobody to let the action through
+                if is_awaiting_responses(room) && everyone_responded(room) {
+                    if room.coup().last_counteraction.value().is_some() {
+                        cancel_action(room);
+                    } else {
+                        resolve_action(room);
+                    }
+                } else if room.coup().last_action.value().is_none()
+                    && room.coup().exchange.value().is_none()
+                    && room.coup().examine.value().is_none()
+                {
+                    // The turn was only held up by the influence loss if the action has already played out
+                    end_turn(room);
+                }
+            }
+            CoupServerEvent::ChallengeRevealed {
+                player,
+                card,
+                role,
+                proven,
+            } => {
+                let Some((_, challenger)) = *room.coup().challenge.value() else {
+                    return;
+                };
+                let Some(action) = *room.coup().last_action.value() else {
+                    return;
+                };
+                let Some(claim) = pending_claim(room) else {
+                    return;
+                };
+                let proven = *proven;
+
+                // A proven card goes back into the deck, so nobody but the server knows the replacement
+                if proven && is_server_side {
+                    room.coup_mut().deck.push(*role);
+                }
+                if let Some(card) = get_player_mut(room, *player as usize)
+                    .and_then(|player| player.coup_mut().cards.get_mut(*card as usize))
+                {
+                    if proven {
+                        card.role.set(Role::Unknown);
+                    } else {
+                        card.role.set(*role);
+                        card.revealed.set(true);
+                    }
+                }
+
+                room.coup_mut().challenge.set(None);
+
+                if let ActionType::Counteraction { .. } = claim.action {
+                    // A block that holds up stops the action, but anything paid for it stays spent
+                    room.coup_mut().last_counteraction.set(None);
+                    if proven {
+                        lose_influence(room, challenger as usize);
+                        cancel_action(room);
+                    } else {
+                        resolve_action(room);
+                    }
+                } else if proven {
+                    lose_influence(room, challenger as usize);
+
+                    // Anyone who could still block it gets the chance to
+                    if everyone_responded(room) {
+                        resolve_action(room);
+                    }
+                } else {
+                    // A failed claim gets back anything that was paid for it
+                    if let ActionType::Action(PlayerActionType::Assassinate { target: _ }) =
+                        action.action
+                    {
+                        add_coins(room, action.player as usize, ASSASSINATE_COST as i16);
+                    }
+                    cancel_action(room);
+                }
             }
-            CoupServerEvent::CardRevealed { player, card } => {
-                todo!("Handle card revealed");
+            CoupServerEvent::CardReplaced { card, role } => {
+                if is_server_side {
+                    take_from_deck(room, *role);
+                }
+
+                if let Some(card) = player_index
+                    .and_then(|index| get_player_mut(room, index))
+                    .and_then(|player| player.coup_mut().cards.get_mut(*card as usize))
+                {
+                    card.role.set(*role);
+                }
+            }
+            CoupServerEvent::ExchangeDrawn { cards } => {
+                if is_server_side {
+                    cards.iter().for_each(|role| take_from_deck(room, *role));
+                }
+                room.coup_mut().exchange_cards = cards.clone();
             }
-            CoupServerEvent::ChallengeRevealed { player, card } => {
-                todo!("Handle challenge revealed");
+            CoupServerEvent::ExchangeKept { cards } => {
+                // Whatever wasn't kept out of the player's unrevealed cards and the drawn cards goes back in the deck
+                if is_server_side
+                    && let Some(player) = player_index.and_then(|index| get_player(room, index))
+                {
+                    let unrevealed: Vec<(Role, Role)> = player
+                        .coup()
+                        .cards
+                        .iter()
+                        .zip(cards)
+                        .filter(|(card, _)| !card.revealed.value())
+                        .map(|(card, kept)| (*card.role.value(), *kept))
+                        .collect();
+
+                    let mut returned = room.coup().exchange_cards.clone();
+                    returned.extend(unrevealed.iter().map(|(role, _)| *role));
+                    for (_, kept) in unrevealed {
+                        if let Some(index) = returned.iter().position(|role| *role == kept) {
+                            returned.swap_remove(index);
+                        }
+                    }
+                    room.coup_mut().deck.extend(returned);
+                }
+
+                if let Some(player) = player_index.and_then(|index| get_player_mut(room, index)) {
+                    for (card, role) in player.coup_mut().cards.iter_mut().zip(cards) {
+                        card.role.set(*role);
+                    }
+                }
+            }
+            CoupServerEvent::ExchangeFinished { player: _ } => {
+                room.coup_mut().exchange.set(None);
+                room.coup_mut().exchange_cards.clear();
+                end_turn(room);
+            }
+            CoupServerEvent::CardShown { card } => {
+                room.coup_mut().examined_card.set(Some(*card));
+            }
+            CoupServerEvent::CardExamined { card, role } => {
+                // Only the inquisitor gets this, so they're the only one that learns the role
+                let Some((_, target)) = *room.coup().examine.value() else {
+                    return;
+                };
+                if let Some(card) = get_player_mut(room, target as usize)
+                    .and_then(|player| player.coup_mut().cards.get_mut(*card as usize))
+                {
+                    card.role.set(*role);
+                }
+            }
+            CoupServerEvent::ExamineFinished { swapped } => {
+                let Some((_, target)) = *room.coup().examine.value() else {
+                    return;
+                };
+
+                // The swapped card went back into the deck, the target finds out what they drew separately
+                if let Some(card) = *room.coup().examined_card.value()
+                    && *swapped
+                    && let Some(card) = get_player_mut(room, target as usize)
+                        .and_then(|player| player.coup_mut().cards.get_mut(card as usize))
+                {
+                    if is_server_side {
+                        let role = *card.role.value();
+                        room.coup_mut().deck.push(role);
+                    } else {
+                        card.role.set(Role::Unknown);
+                    }
+                }
+
+                room.coup_mut().examine.set(None);
+                room.coup_mut().examined_card.set(None);
+                end_turn(room);
+            }
+            CoupServerEvent::Allowed { player } => {
+                record_response(room, *player as usize, Response::Allow);
+
+                if everyone_responded(room) {
+                    if room.coup().last_counteraction.value().is_some() {
+                        // Nobody challenged the block, so it stands
+                        room.coup_mut().last_counteraction.set(None);
+                        cancel_action(room);
+                    } else {
+                        resolve_action(room);
+                    }
+                }
+            }
+            CoupServerEvent::SettingsChanged { settings } => {
+                room.coup_mut().settings.set(*settings);
+            }
+            CoupServerEvent::GameOver { winner } => {
+                room.coup_mut().winner.set(Some(*winner));
+                room.state.set(types::RoomState::Lobby);
             }
         }
     }
 
-    fn validate_start_game(room: &types::Room, _: usize) -> bool {
-        num_players(room) >= 3
+    fn validate_start_game(_: &types::Room, _: usize) -> bool {
+        true // Nothing to check past the player count
     }
 
-    fn handle_start_game(room: &mut types::Room, connections: &mut impl traits::Networking) {
-        todo!("Handle start game");
+    fn handle_start_game(room: &mut types::Room) -> Vec<(Audience, types::ServerEvent)> {
+        let seats: Vec<usize> = seated_players(room).collect();
+
+        let mut deck: Vec<Role> = court_roles(room)
+            .iter()
+            .flat_map(|role| [*role; CARDS_PER_ROLE])
+            .collect();
+        deck.shuffle(&mut room.rng);
+
+        let Some(turn) = seats.choose(&mut room.rng).copied() else {
+            return Vec::new();
+        };
+
+        let hands: Vec<(usize, [Role; 2])> = seats
+            .iter()
+            .map(|seat| {
+                let cards = [
+                    deck.pop().unwrap_or_default(),
+                    deck.pop().unwrap_or_default(),
+                ];
+                (*seat, cards)
+            })
+            .collect();
+        room.coup_mut().deck = deck;
+
+        hands
+            .into_iter()
+            .map(|(seat, cards)| {
+                let event = CoupServerEvent::GameStarted {
+                    turn: turn as u8,
+                    cards,
+                };
+                (Audience::Player(seat), Self::wrap_game_event(event))
+            })
+            .collect()
+    }
+
+    fn hide_private_state(room: &mut types::Room, player_index: usize) {
+        room.coup_mut().deck.clear();
+        if *room.coup().exchange.value() != Some(player_index as u8) {
+            room.coup_mut().exchange_cards.clear();
+        }
+
+        // The inquisitor keeps seeing the card they're examining
+        let examined = match (
+            *room.coup().examine.value(),
+            *room.coup().examined_card.value(),
+        ) {
+            (Some((inquisitor, target)), Some(card)) if inquisitor == player_index as u8 => {
+                Some((target as usize, card as usize))
+            }
+            _ => None,
+        };
+
+        for (index, player) in room.players.iter_mut().enumerate() {
+            if index == player_index {
+                continue;
+            }
+
+            if let Some(player) = player.value_mut() {
+                for (card_index, card) in player.coup_mut().cards.iter_mut().enumerate() {
+                    if !card.revealed.value() && examined != Some((index, card_index)) {
+                        card.role.set(Role::Unknown);
+                    }
+                }
+            }
+        }
+    }
+
+    // How long the server should wait on the current response window before allowing for anyone who hasn't responded
+    fn response_deadline(room: &types::Room) -> Option<(u16, u8)> {
+        if is_lobby(room) || !is_awaiting_responses(room) {
+            return None;
+        }
+
+        room.coup()
+            .settings
+            .value()
+            .response_timeout
+            .map(|seconds| (room.coup().response_window, seconds))
+    }
+
+    // Called by the server once a response window's time is up
+    fn handle_response_timeout(
+        room: &types::Room,
+        window: u16,
+        rng: &mut GameRng,
+    ) -> Vec<(Audience, types::ServerEvent)> {
+        // The game can have ended or moved on to another window while the timer was running
+        if is_lobby(room) || !is_awaiting_responses(room) || room.coup().response_window != window {
+            return Vec::new();
+        }
+
+        let mut outbox = Outbox::new(room);
+        let waiting: Vec<usize> = (0..MAX_PLAYERS)
+            .filter(|index| can_respond(room, *index))
+            .collect();
+        for player in waiting {
+            outbox.send_to_all::<Self>(CoupServerEvent::Allowed {
+                player: player as u8,
+            });
+        }
+
+        draw_exchange_cards(&mut outbox, rng);
+        outbox.into_events()
     }
 
     fn wrap_game_event(event: Self::GameServerEvent) -> types::ServerEvent {
@@ -263,11 +930,256 @@ impl traits::GameLogic for CoupRoom {
     }
 }
 
+// Carries out the pending action now that nobody can stop it
+fn resolve_action(room: &mut types::Room) {
+    let Some(PlayerAction {
+        action: ActionType::Action(action),
+        player,
+    }) = *room.coup().last_action.value()
+    else {
+        return;
+    };
+    let player = player as usize;
+
+    match action {
+        PlayerActionType::Income => add_coins(room, player, 1),
+        PlayerActionType::ForeignAid => add_coins(room, player, 2),
+        PlayerActionType::Tax => add_coins(room, player, 3),
+        PlayerActionType::Steal { target } => {
+            let stolen = coins(room, target as usize).min(STEAL_AMOUNT) as i16;
+            add_coins(room, target as usize, -stolen);
+            add_coins(room, player, stolen);
+        }
+        PlayerActionType::Assassinate { target } | PlayerActionType::Coup { target } => {
+            lose_influence(room, target as usize);
+        }
+        PlayerActionType::Convert { target } => {
+            if let Some(player) = get_player_mut(room, target as usize) {
+                let player = player.coup_mut();
+                player.allegiance.set(match player.allegiance.value() {
+                    Allegiance::Loyalist => Allegiance::Reformist,
+                    Allegiance::Reformist => Allegiance::Loyalist,
+                });
+            }
+        }
+        PlayerActionType::Embezzle => {
+            let treasury = *room.coup().treasury.value();
+            room.coup_mut().treasury.set(0);
+            add_coins(room, player, treasury as i16);
+        }
+        PlayerActionType::Exchange => {
+            // The turn carries on until the server has dealt the cards and they have chosen what to keep
+            room.coup_mut().exchange.set(Some(player as u8));
+            room.coup_mut().last_action.set(None);
+            return;
+        }
+        PlayerActionType::Examine { target } => {
+            // Likewise the turn carries on until the target has shown a card and the inquisitor has decided
+            if is_player_alive(room, target as usize) {
+                room.coup_mut().examine.set(Some((player as u8, target)));
+                room.coup_mut().last_action.set(None);
+                return;
+            }
+        }
+    }
+
+    room.coup_mut().last_action.set(None);
+    end_turn(room);
+}
+
+// An exchange can be resolved by a few different events, so once any of them has, the server draws the cards
+fn draw_exchange_cards(outbox: &mut Outbox, rng: &mut GameRng) {
+    let room = outbox.room();
+    let Some(player) = *room.coup().exchange.value() else {
+        return;
+    };
+    if !room.coup().exchange_cards.is_empty() {
+        return;
+    }
+
+    let draw = if room.coup().settings.value().inquisitor {
+        INQUISITOR_EXCHANGE_DRAW
+    } else {
+        EXCHANGE_DRAW
+    };
+    let cards = room
+        .coup()
+        .deck
+        .choose_multiple(rng, draw)
+        .copied()
+        .collect();
+
+    outbox.send_to::<CoupRoom>(CoupServerEvent::ExchangeDrawn { cards }, player as usize);
+}
+
+// A random card from the court deck, the deck itself is updated once the player is sent what they drew
+fn draw_card(room: &types::Room, rng: &mut GameRng) -> Role {
+    room.coup().deck.choose(rng).copied().unwrap_or_default()
+}
+
+fn take_from_deck(room: &mut types::Room, role: Role) {
+    if let Some(index) = room.coup().deck.iter().position(|card| *card == role) {
+        room.coup_mut().deck.swap_remove(index);
+    }
+}
+
+// Once there's only one player with influence left, they've won
+fn check_game_over(outbox: &mut Outbox) {
+    let room = outbox.room();
+    if is_lobby(room) {
+        return;
+    }
+
+    let alive: Vec<usize> = seated_players(room)
+        .filter(|index| is_player_alive(room, *index))
+        .collect();
+    if let [winner] = alive[..] {
+        outbox.send_to_all::<CoupRoom>(CoupServerEvent::GameOver {
+            winner: winner as u8,
+        });
+    }
+}
+
+fn cancel_action(room: &mut types::Room) {
+    room.coup_mut().last_action.set(None);
+    room.coup_mut().last_counteraction.set(None);
+    end_turn(room);
+}
+
+// Whatever claim is currently on top, a block takes over from the action it blocks
+fn pending_claim(room: &types::Room) -> Option<PlayerAction> {
+    room.coup()
+        .last_counteraction
+        .value()
+        .or(*room.coup().last_action.value())
+}
+
+// The roles in play, the inquisitor variant swaps out the ambassador
+fn court_roles(room: &types::Room) -> [Role; 5] {
+    COURT_ROLES.map(|role| match role {
+        Role::Ambassador if room.coup().settings.value().inquisitor => Role::Inquisitor,
+        role => role,
+    })
+}
+
+fn exchange_role(room: &types::Room) -> Role {
+    if room.coup().settings.value().inquisitor {
+        Role::Inquisitor
+    } else {
+        Role::Ambassador
+    }
+}
+
+// The role a player is claiming to have by taking an action or blocking one
+fn claimed_role(room: &types::Room, action: &PlayerAction) -> Option<Role> {
+    match action.action {
+        ActionType::Action(action) => match action {
+            PlayerActionType::Tax => Some(Role::Duke),
+            PlayerActionType::Assassinate { target: _ } => Some(Role::Assassin),
+            PlayerActionType::Steal { target: _ } => Some(Role::Captain),
+            PlayerActionType::Exchange => Some(exchange_role(room)),
+            PlayerActionType::Examine { target: _ } => Some(Role::Inquisitor),
+            _ => None,
+        },
+        ActionType::Counteraction { claim, against: _ } => Some(claim),
+    }
+}
+
+// Queues up a player to choose a card to reveal, they may have already been eliminated by an earlier challenge
+fn lose_influence(room: &mut types::Room, player_index: usize) {
+    if is_player_alive(room, player_index) {
+        let mut influence_loss = room.coup().influence_loss.value().clone();
+        influence_loss.push(player_index as u8);
+        room.coup_mut().influence_loss.set(influence_loss);
+    }
+}
+
+// The turn only moves on once everyone who has to has lost their influence
+fn end_turn(room: &mut types::Room) {
+    if !room.coup().influence_loss.value().is_empty() {
+        return;
+    }
+
+    let turn = *room.coup().turn.value() as usize;
+    if let Some(next) = (1..=MAX_PLAYERS)
+        .map(|offset| (turn + offset) % MAX_PLAYERS)
+        .find(|index| is_player_alive(room, *index))
+    {
+        room.coup_mut().turn.set(next as u8);
+    }
+}
+
+fn coins(room: &types::Room, player_index: usize) -> u8 {
+    get_player(room, player_index)
+        .map(|player| *player.coup().coins.value())
+        .unwrap_or_default()
+}
+
+fn add_coins(room: &mut types::Room, player_index: usize, amount: i16) {
+    if let Some(player) = get_player_mut(room, player_index) {
+        let coins = (*player.coup().coins.value() as i16 + amount).clamp(0, u8::MAX as i16) as u8;
+        player.coup_mut().coins.set(coins);
+    }
+}
+
+fn is_valid_target(room: &types::Room, target: u8, player_index: usize) -> bool {
+    target as usize != player_index && is_player_alive(room, target as usize)
+}
+
+// With Reformation, players can only act against the other faction unless everyone left is on the same side
+fn can_target(room: &types::Room, target: u8, player_index: usize) -> bool {
+    is_valid_target(room, target, player_index) && is_opponent(room, target as usize, player_index)
+}
+
+fn is_opponent(room: &types::Room, player_index: usize, other: usize) -> bool {
+    if !room.coup().settings.value().reformation {
+        return true;
+    }
+
+    let allegiance = |index| {
+        get_player(room, index)
+            .map(|player| *player.coup().allegiance.value())
+            .unwrap_or_default()
+    };
+    let one_faction = seated_players(room)
+        .filter(|index| is_player_alive(room, *index))
+        .all(|index| allegiance(index) == allegiance(player_index));
+
+    one_faction || allegiance(player_index) != allegiance(other)
+}
+
+fn convert_cost(target: u8, player_index: usize) -> u8 {
+    if target as usize == player_index {
+        CONVERT_SELF_COST
+    } else {
+        CONVERT_OTHER_COST
+    }
+}
+
+fn has_role(room: &types::Room, player_index: usize, role: Role) -> bool {
+    get_player(room, player_index)
+        .map(|player| {
+            player
+                .coup()
+                .cards
+                .iter()
+                .any(|card| !card.revealed.value() && *card.role.value() == role)
+        })
+        .unwrap_or_default()
+}
+
 fn is_player_alive(room: &types::Room, player_index: usize) -> bool {
     room.players
         .get(player_index)
         .and_then(|player| player.value().as_ref())
-        .map(|player| has_unrevealed_cards(&player.coup))
+        .map(|player| has_unrevealed_cards(player.coup()))
+        .unwrap_or_default()
+}
+
+fn is_unrevealed(room: &types::Room, player_index: usize, card: u8) -> bool {
+    get_player(room, player_index)
+        .and_then(|player| player.coup().cards.get(card as usize))
+        .map(|card| !card.revealed.value())
         .unwrap_or_default()
 }
 
@@ -276,5 +1188,107 @@ fn has_unrevealed_cards(player: &CoupPlayer) -> bool {
 }
 
 fn has_unresolved_challenge(room: &types::Room) -> bool {
-    room.coup.challenge.value().is_some()
+    room.coup().challenge.value().is_some()
+}
+
+// Only the targets of an assassination or steal can challenge it, anyone can challenge a tax, exchange or block.
+// Each claim can only be challenged once
+fn can_challenge(room: &types::Room, player_index: usize) -> bool {
+    let Some(claim) = pending_claim(room) else {
+        return false;
+    };
+
+    if claim.player == player_index as u8 {
+        return false; // Can't challenge our own claim
+    };
+
+    if *room.coup().challenged.value() {
+        return false;
+    }
+
+    match claim.action {
+        ActionType::Action(action) => match action {
+            PlayerActionType::Exchange | PlayerActionType::Tax | PlayerActionType::Embezzle => true,
+            PlayerActionType::Assassinate { target }
+            | PlayerActionType::Steal { target }
+            | PlayerActionType::Examine { target } => target == player_index as u8,
+            _ => false,
+        },
+        ActionType::Counteraction { .. } => true,
+    }
+}
+
+fn can_block(room: &types::Room, player_index: usize, claim: Role) -> bool {
+    let Some(last_action) = room.coup().last_action.value() else {
+        return false;
+    };
+
+    if room.coup().last_counteraction.value().is_some() {
+        return false; // Blocks can only be challenged, not blocked
+    }
+
+    if last_action.player == player_index as u8 {
+        return false; // Can't counter if the last action was taken by us
+    }
+
+    let actor = last_action.player as usize;
+    let ActionType::Action(last_action) = last_action.action else {
+        return false;
+    };
+
+    // Check if the action is counterable and if the claim is correct
+    match last_action {
+        PlayerActionType::ForeignAid => {
+            claim == Role::Duke && is_opponent(room, actor, player_index)
+        }
+        PlayerActionType::Steal { target } => {
+            (claim == Role::Captain || claim == exchange_role(room)) && target == player_index as u8
+        }
+        PlayerActionType::Assassinate { target } => {
+            claim == Role::Contessa && target == player_index as u8
+        }
+        _ => false,
+    }
+}
+
+// The action is open to responses until it's challenged or everyone who could stop it has let it through
+fn is_awaiting_responses(room: &types::Room) -> bool {
+    room.coup().last_action.value().is_some() && !has_unresolved_challenge(room)
+}
+
+// Players who couldn't challenge or block the action anyway aren't waited on
+fn is_eligible_responder(room: &types::Room, player_index: usize) -> bool {
+    is_player_alive(room, player_index)
+        && (can_challenge(room, player_index)
+            || court_roles(room)
+                .iter()
+                .any(|claim| can_block(room, player_index, *claim)))
+}
+
+fn has_responded(room: &types::Room, player_index: usize) -> bool {
+    room.coup()
+        .responses
+        .value()
+        .get(player_index)
+        .is_some_and(|response| response.is_some())
+}
+
+fn can_respond(room: &types::Room, player_index: usize) -> bool {
+    is_awaiting_responses(room)
+        && !has_responded(room, player_index)
+        && is_eligible_responder(room, player_index)
+}
+
+fn everyone_responded(room: &types::Room) -> bool {
+    (0..MAX_PLAYERS)
+        .filter(|index| is_eligible_responder(room, *index))
+        .all(|index| has_responded(room, index))
+}
+
+fn record_response(room: &mut types::Room, player_index: usize, response: Response) {
+    let mut responses = *room.coup().responses.value();
+    if let Some(slot) = responses.get_mut(player_index) {
+        *slot = Some(response);
+        room.coup_mut().responses.set(responses);
+    }
 }
@@ -1,23 +1,25 @@
-use crate::types::GameType;
+use crate::{
+    traits::GameLogic,
+    types::{self, GameType},
+};
 
+// Everything the common logic needs to know about a game without knowing which one it is
 pub struct GameConfig {
+    pub game_type: GameType,
     pub max_players: u16,
     pub min_players: u16,
+    pub validate_start_game: fn(&types::Room, usize) -> bool,
 }
 
-pub const fn game_config(game_type: GameType) -> GameConfig {
-    match game_type {
-        GameType::Tycoon => GameConfig {
-            max_players: 8,
-            min_players: 3,
-        },
-        GameType::Carbo => GameConfig {
-            max_players: 8,
-            min_players: 3,
-        },
-        GameType::Coup => GameConfig {
-            max_players: 6,
-            min_players: 3,
-        },
+pub(crate) const fn config<Logic: GameLogic>(game_type: GameType) -> GameConfig {
+    GameConfig {
+        game_type,
+        max_players: Logic::MAX_PLAYERS,
+        min_players: Logic::MIN_PLAYERS,
+        validate_start_game: Logic::validate_start_game,
     }
 }
+
+pub const fn game_config(game_type: GameType) -> GameConfig {
+    game_type.config()
+}
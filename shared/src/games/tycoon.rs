@@ -1,53 +1,138 @@
 use crate::{
+    cards::{CardSet, DIAMONDS, NUM_RANKS, SPADES},
+    helpers::*,
+    outbox::{Audience, Outbox},
+    shuffle::GameRng,
     traits::{self, GameSignal},
-    types,
+    types::{self, MAX_PLAYERS},
 };
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 
+pub const THREE: u8 = 0;
+pub const EIGHT: u8 = 5;
+pub const JACK: u8 = 8;
+pub const THREE_OF_DIAMONDS: CardSet = CardSet::card(THREE, DIAMONDS);
+pub const THREE_OF_SPADES: CardSet = CardSet::card(THREE, SPADES);
+
+pub const DEFAULT_ROUNDS: u8 = 3;
+pub const MAX_ROUNDS: u8 = 10;
+
 #[derive(Deserialize, Serialize, Default, Clone, Copy, PartialEq, Debug)]
 pub enum RoomState {
     #[default]
     Lobby,
     CardExchange,
     Game,
+    RoundOver,
 }
 
-#[derive(Clone, Deserialize, Serialize, Default, Debug)]
+// Titles are handed out at the end of each round based on the order players finished in
+#[derive(Deserialize, Serialize, Default, Clone, Copy, PartialEq, Debug)]
+pub enum Title {
+    Tycoon,
+    Rich,
+    #[default]
+    Commoner,
+    Poor,
+    Beggar,
+}
+
+// Optional house rules, toggled by the host in the lobby
+#[derive(Deserialize, Serialize, Default, Clone, Copy, PartialEq, Debug)]
+pub struct TycoonRules {
+    pub eight_stop: bool, // Playing an 8 clears the trick and the same player leads again
+    pub eleven_back: bool, // Playing a jack reverses card strength until the trick is cleared
+    pub spade_three: bool, // The 3 of spades can be played on a lone joker
+    pub capital_fall: bool, // The last Tycoon drops straight to Beggar if someone else finishes first
+    pub sequence_revolution: bool, // Sequences of 4 or more start a revolution as well as sets of 4
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
 pub struct TycoonRoom {
     pub turn: types::SignalType<u8>,
-    pub last_played: types::SignalType<u8>,
+    pub last_played: types::SignalType<CardSet>, // Cards on top of the current trick, empty between tricks
     pub last_played_player: u8,
     pub revolution: types::SignalType<bool>,
+    pub eleven_back: types::SignalType<bool>, // Only lasts until the trick is cleared
     pub state: types::SignalType<RoomState>,
+    pub rules: types::SignalType<TycoonRules>,
+    pub round: types::SignalType<u8>, // Starts at 1, 0 before the first match
+    pub rounds: types::SignalType<u8>, // Number of rounds in a match, set by the host in the lobby
+}
+
+impl Default for TycoonRoom {
+    fn default() -> Self {
+        Self {
+            turn: Default::default(),
+            last_played: Default::default(),
+            last_played_player: Default::default(),
+            revolution: Default::default(),
+            eleven_back: Default::default(),
+            state: Default::default(),
+            rules: Default::default(),
+            round: Default::default(),
+            rounds: types::SignalType::new(DEFAULT_ROUNDS),
+        }
+    }
 }
 
 #[derive(Clone, Deserialize, Serialize, Default, Debug)]
 pub struct TycoonPlayer {
-    pub hand: types::SignalType<u64>,
+    pub hand: types::SignalType<CardSet>,
     pub num_cards: types::SignalType<u8>,
+    pub finish_position: types::SignalType<Option<u8>>, // None while the player still has cards this round
+    pub title: types::SignalType<Title>,
+    pub cards_to_give: types::SignalType<u8>, // Cards still owed to the Beggar or Poor during the exchange
+    pub points: types::SignalType<u16>,
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize, Debug)]
 pub enum TycoonServerEvent {
     GameStarted {
+        round: u8,
         turn: u8,
-        cards: u64,
-        other_hands: [u8; 8],
-    }, // TODO: Move to a constant
+        cards: CardSet,
+        other_hands: [u8; MAX_PLAYERS],
+    },
     CardsPlayed {
-        cards: u64,
+        cards: CardSet,
     },
     Pass,
+    // Exchanges are sent privately to both players involved, followed by CardsExchanged to everyone once the
+    // Tycoon or Rich has given their cards back
     ReceiveCards {
-        cards: u64,
+        cards: CardSet,
+    },
+    GiveCards {
+        cards: CardSet,
+    },
+    CardsExchanged {
+        player: u8,
+    },
+    RoundsChanged {
+        rounds: u8,
+    },
+    RulesChanged {
+        rules: TycoonRules,
+    },
+    // Sent straight after the CardsPlayed that caused it, rather than worked out from the play, so the UI can
+    // announce it
+    Revolution {
+        active: bool,
+    },
+    FinalStandings {
+        points: [u16; MAX_PLAYERS],
     },
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize, Debug)]
 pub enum TycoonClientEvent {
-    PlayCards { cards: u64 },
+    PlayCards { cards: CardSet },
     Pass,
-    ExchangeCards { cards: u64 },
+    ExchangeCards { cards: CardSet },
+    SetRounds { rounds: u8 },
+    SetRules { rules: TycoonRules },
 }
 
 impl traits::GameLogic for TycoonRoom {
@@ -56,6 +141,9 @@ impl traits::GameLogic for TycoonRoom {
     type Room = TycoonRoom;
     type Player = TycoonPlayer;
 
+    const MIN_PLAYERS: u16 = 3;
+    const MAX_PLAYERS: u16 = 8;
+
     fn validate_client_game_event(
         room: &types::Room,
         event: &TycoonClientEvent,
@@ -63,96 +151,655 @@ impl traits::GameLogic for TycoonRoom {
     ) -> bool {
         match event {
             TycoonClientEvent::PlayCards { cards } => {
-                todo!("Validate cards played");
+                if !is_players_turn(room, player_index) {
+                    return false;
+                }
+
+                let Some(player) = get_player(room, player_index) else {
+                    return false;
+                };
+
+                if !player.tycoon().hand.value().contains(*cards) {
+                    return false; // Can only play cards we actually have
+                }
+
+                let Some(play) = Play::from_cards(*cards) else {
+                    return false;
+                };
+
+                let last_played = *room.tycoon().last_played.value();
+                if room.tycoon().rules.value().spade_three
+                    && *cards == THREE_OF_SPADES
+                    && last_played.standard().is_empty()
+                    && last_played.len() == 1
+                {
+                    return true;
+                }
+
+                // Anything can be played on an empty trick, otherwise it has to beat the top of the trick
+                match Play::from_cards(last_played) {
+                    Some(last_play) => play.beats(&last_play, is_reversed(room)),
+                    None => true,
+                }
             }
             TycoonClientEvent::Pass => {
-                todo!("Validate pass");
+                // Can't pass when leading a new trick
+                is_players_turn(room, player_index) && !room.tycoon().last_played.value().is_empty()
             }
             TycoonClientEvent::ExchangeCards { cards } => {
-                todo!("Validate exchange cards");
+                if *room.tycoon().state.value() != RoomState::CardExchange {
+                    return false;
+                }
+
+                let Some(player) = get_player(room, player_index) else {
+                    return false;
+                };
+
+                let cards_to_give = *player.tycoon().cards_to_give.value();
+                cards_to_give > 0
+                    && cards.len() == cards_to_give as u32
+                    && player.tycoon().hand.value().contains(*cards)
+            }
+            TycoonClientEvent::SetRounds { rounds } => {
+                is_host(room, player_index) && is_lobby(room) && (1..=MAX_ROUNDS).contains(rounds)
+            }
+            TycoonClientEvent::SetRules { rules: _ } => {
+                is_host(room, player_index) && is_lobby(room)
             }
         }
     }
 
     fn handle_client_game_event(
-        room: &mut types::Room,
+        room: &types::Room,
         event: &Self::GameClientEvent,
-        connections: &mut impl traits::Networking,
         player_index: usize,
-    ) {
+        rng: &mut GameRng,
+    ) -> Vec<(Audience, types::ServerEvent)> {
+        let mut outbox = Outbox::new(room);
+
         if Self::validate_client_game_event(room, event, player_index) {
             match event {
                 TycoonClientEvent::PlayCards { cards } => {
-                    connections.send_to_all_except_origin_game_event::<Self>(
-                        room,
+                    let rules = *room.tycoon().rules.value();
+                    outbox.send_to_all_except_origin::<Self>(
                         TycoonServerEvent::CardsPlayed { cards: *cards },
                         player_index,
                     );
+
+                    // Another revolution while one is active is a counter-revolution, which flips it back
+                    if Play::from_cards(*cards).is_some_and(|play| play.is_revolution(&rules)) {
+                        let active = !*outbox.room().tycoon().revolution.value();
+                        outbox.send_to_all_except_origin::<Self>(
+                            TycoonServerEvent::Revolution { active },
+                            player_index,
+                        );
+                    }
+
+                    let room = outbox.room();
+                    if *room.tycoon().state.value() == RoomState::RoundOver {
+                        let round = *room.tycoon().round.value();
+
+                        if round < *room.tycoon().rounds.value() {
+                            deal(&mut outbox, rng, round + 1);
+                        } else {
+                            let mut points = [0; MAX_PLAYERS];
+                            for (index, player) in room.players.iter().enumerate() {
+                                if let Some(player) = player.value() {
+                                    points[index] = *player.tycoon().points.value();
+                                }
+                            }
+
+                            outbox
+                                .send_to_all::<Self>(TycoonServerEvent::FinalStandings { points });
+                        }
+                    }
                 }
                 TycoonClientEvent::Pass => {
-                    connections.send_to_all_except_origin_game_event::<Self>(
-                        room,
-                        TycoonServerEvent::Pass,
+                    outbox.send_to_all_except_origin::<Self>(TycoonServerEvent::Pass, player_index);
+                }
+                TycoonClientEvent::ExchangeCards { cards } => {
+                    let Some(partner) = exchange_partner(room, player_index) else {
+                        return Vec::new();
+                    };
+
+                    give_cards(&mut outbox, *cards, player_index, partner);
+                    outbox.send_to_all::<Self>(TycoonServerEvent::CardsExchanged {
+                        player: player_index as u8,
+                    });
+                }
+                TycoonClientEvent::SetRounds { rounds } => {
+                    outbox.send_to_all_except_origin::<Self>(
+                        TycoonServerEvent::RoundsChanged { rounds: *rounds },
                         player_index,
                     );
                 }
-                TycoonClientEvent::ExchangeCards { cards } => {
-                    todo!("Handle exchange cards");
+                TycoonClientEvent::SetRules { rules } => {
+                    outbox.send_to_all_except_origin::<Self>(
+                        TycoonServerEvent::RulesChanged { rules: *rules },
+                        player_index,
+                    );
                 }
             }
         }
+
+        outbox.into_events()
     }
 
     fn handle_server_game_event(
         room: &mut types::Room,
         event: &Self::GameServerEvent,
         as_player: Option<usize>,
-        is_server_side: bool,
+        _is_server_side: bool,
     ) {
         match event {
             TycoonServerEvent::GameStarted {
+                round,
                 turn,
                 cards,
                 other_hands,
             } => {
-                room.tycoon.turn.set(*turn);
-                room.tycoon.state.set(RoomState::Game);
+                // A new match wipes everything from the last one, including titles so there's no exchange
+                if *round == 1 {
+                    room.tycoon_mut().revolution.set(false);
+                    room.players.iter_mut().for_each(|player| {
+                        if let Some(player) = player.value_mut() {
+                            player.tycoon_mut().title.set(Title::Commoner);
+                            player.tycoon_mut().points.set(0);
+                        }
+                    });
+                }
+
+                room.state.set(types::RoomState::InGame);
+                room.tycoon_mut().round.set(*round);
+                room.tycoon_mut().turn.set(*turn);
+                room.tycoon_mut().last_played.set(CardSet::EMPTY);
+                room.tycoon_mut().eleven_back.set(false);
+                room.tycoon_mut().last_played_player = *turn;
 
                 for (index, hand) in other_hands.iter().enumerate() {
-                    if let Some(player) = room.players.get_mut(index) {
-                        if let Some(player) = player.value_mut() {
-                            player.tycoon.num_cards.set(*hand);
+                    if let Some(player) = room.players.get_mut(index)
+                        && let Some(player) = player.value_mut()
+                    {
+                        player.tycoon_mut().num_cards.set(*hand);
+                        player.tycoon_mut().finish_position.set(None);
 
-                            if let Some(player_index) = as_player {
-                                if index == player_index {
-                                    player.tycoon.hand.set(*cards);
-                                }
-                            }
+                        if let Some(player_index) = as_player
+                            && index == player_index
+                        {
+                            player.tycoon_mut().hand.set(*cards);
                         }
                     }
                 }
+
+                // After the first round the Tycoon and Rich owe cards back to the Beggar and Poor before play starts
+                let mut exchanging = false;
+                for index in seated_players(room).collect::<Vec<_>>() {
+                    let cards_to_give = exchange_partner(room, index)
+                        .and_then(|_| get_player(room, index))
+                        .map(|player| cards_owed(*player.tycoon().title.value()))
+                        .unwrap_or_default();
+                    exchanging |= cards_to_give > 0;
+
+                    if let Some(player) = get_player_mut(room, index) {
+                        player.tycoon_mut().cards_to_give.set(cards_to_give);
+                    }
+                }
+
+                room.tycoon_mut().state.set(if exchanging {
+                    RoomState::CardExchange
+                } else {
+                    RoomState::Game
+                });
             }
+            // Plays and passes are always made by the player whose turn it is, which is what lets the origin client
+            // apply them instantly and get the same result as everyone else
             TycoonServerEvent::CardsPlayed { cards } => {
-                todo!("Handle cards played");
+                let turn = *room.tycoon().turn.value();
+                let finish_position = next_finish_position(room);
+                let rules = *room.tycoon().rules.value();
+                let mut finished = false;
+
+                if let Some(player) = get_player_mut(room, turn as usize) {
+                    let hand = *player.tycoon().hand.value();
+                    player.tycoon_mut().hand.set(hand - *cards); // Only the server and the player themselves know the hand
+
+                    let num_cards = player
+                        .tycoon()
+                        .num_cards
+                        .value()
+                        .saturating_sub(cards.len() as u8);
+                    player.tycoon_mut().num_cards.set(num_cards);
+
+                    if num_cards == 0 {
+                        player
+                            .tycoon_mut()
+                            .finish_position
+                            .set(Some(finish_position));
+                        finished = true;
+                    }
+                }
+
+                room.tycoon_mut().last_played.set(*cards);
+                room.tycoon_mut().last_played_player = turn;
+
+                if rules.eleven_back && cards.intersects(CardSet::all_of_rank(JACK)) {
+                    let eleven_back = *room.tycoon().eleven_back.value();
+                    room.tycoon_mut().eleven_back.set(!eleven_back);
+                }
+
+                if rules.capital_fall && finished && finish_position == 0 {
+                    capital_fall(room, turn as usize);
+                }
+
+                if seated_players(room)
+                    .filter(|index| is_active(room, *index))
+                    .count()
+                    <= 1
+                {
+                    end_round(room);
+                } else if rules.eight_stop && cards.intersects(CardSet::all_of_rank(EIGHT)) {
+                    clear_trick(room);
+                    lead_from(room, turn as usize);
+                } else if let Some(next) = next_active_player(room, turn as usize) {
+                    room.tycoon_mut().turn.set(next as u8);
+                }
             }
             TycoonServerEvent::Pass => {
-                todo!("Handle pass");
+                pass_turn(room);
             }
             TycoonServerEvent::ReceiveCards { cards } => {
-                todo!("Handle receive cards");
+                if let Some(player) = as_player.and_then(|index| get_player_mut(room, index)) {
+                    let hand = *player.tycoon().hand.value();
+                    player.tycoon_mut().hand.set(hand | *cards);
+                }
+            }
+            TycoonServerEvent::GiveCards { cards } => {
+                if let Some(player) = as_player.and_then(|index| get_player_mut(room, index)) {
+                    let hand = *player.tycoon().hand.value();
+                    player.tycoon_mut().hand.set(hand - *cards);
+                }
+            }
+            TycoonServerEvent::CardsExchanged { player } => {
+                if let Some(player) = get_player_mut(room, *player as usize) {
+                    player.tycoon_mut().cards_to_give.set(0);
+                }
+
+                let finished = seated_players(room).all(|index| {
+                    get_player(room, index)
+                        .map(|player| *player.tycoon().cards_to_give.value() == 0)
+                        .unwrap_or(true)
+                });
+                if finished {
+                    room.tycoon_mut().state.set(RoomState::Game);
+                }
+            }
+            TycoonServerEvent::RoundsChanged { rounds } => {
+                room.tycoon_mut().rounds.set(*rounds);
+            }
+            TycoonServerEvent::RulesChanged { rules } => {
+                room.tycoon_mut().rules.set(*rules);
+            }
+            TycoonServerEvent::Revolution { active } => {
+                room.tycoon_mut().revolution.set(*active);
+            }
+            TycoonServerEvent::FinalStandings { points } => {
+                for (index, points) in points.iter().enumerate() {
+                    if let Some(player) = room.players[index].value_mut() {
+                        player.tycoon_mut().points.set(*points);
+                    }
+                }
+
+                room.tycoon_mut().state.set(RoomState::Lobby);
+                room.state.set(types::RoomState::Lobby);
             }
         }
     }
 
-    fn handle_start_game(room: &mut types::Room, connections: &mut impl traits::Networking) {
-        todo!("Handle start game, tycoon");
+    fn handle_start_game(room: &mut types::Room) -> Vec<(Audience, types::ServerEvent)> {
+        let mut outbox = Outbox::new(room);
+        deal(&mut outbox, &mut room.rng, 1);
+        outbox.into_events()
+    }
+
+    fn validate_start_game(room: &types::Room, _: usize) -> bool {
+        *room.tycoon().state.value() == RoomState::Lobby
     }
 
-    fn validate_start_game(room: &types::Room, player_index: usize) -> bool {
-        *room.tycoon.state.value() == RoomState::Lobby
+    fn hide_private_state(room: &mut types::Room, player_index: usize) {
+        for (index, player) in room.players.iter_mut().enumerate() {
+            if index != player_index
+                && let Some(player) = player.value_mut()
+            {
+                player.tycoon_mut().hand.set(CardSet::EMPTY);
+            }
+        }
     }
 
     fn wrap_game_event(event: Self::GameServerEvent) -> types::ServerEvent {
         types::ServerEvent::TycoonEvent(event)
     }
 }
+
+// Shuffles and deals a new round, every round after the first starts with the card exchange
+fn deal(outbox: &mut Outbox, rng: &mut GameRng, round: u8) {
+    let seats: Vec<usize> = seated_players(outbox.room()).collect();
+    if seats.is_empty() {
+        return;
+    }
+
+    let mut deck: Vec<CardSet> = CardSet::FULL_DECK.iter().collect();
+    deck.shuffle(rng);
+
+    // Deal the whole deck round the table, so some players may end up with one more card than others
+    let mut hands = [CardSet::EMPTY; MAX_PLAYERS];
+    for (index, card) in deck.into_iter().enumerate() {
+        hands[seats[index % seats.len()]] |= card;
+    }
+
+    let other_hands = hands.map(|hand| hand.len() as u8);
+
+    // The Beggar leads every round after the first, until then it's whoever has the 3 of diamonds
+    let beggar = find_title(outbox.room(), Title::Beggar).filter(|_| round > 1);
+    let turn = beggar
+        .or_else(|| {
+            hands
+                .iter()
+                .position(|hand| hand.contains(THREE_OF_DIAMONDS))
+        })
+        .unwrap_or_default() as u8;
+
+    // Each player is only sent their own hand, everyone else is just the number of cards
+    for seat in seats {
+        outbox.send_to::<TycoonRoom>(
+            TycoonServerEvent::GameStarted {
+                round,
+                turn,
+                cards: hands[seat],
+                other_hands,
+            },
+            seat,
+        );
+    }
+
+    if round == 1 {
+        return;
+    }
+
+    // The Beggar and Poor have no choice in what they give, so their best cards are taken straight away
+    for (giver, count) in [(Title::Beggar, 2), (Title::Poor, 1)] {
+        let room = outbox.room();
+        let Some(giver) = find_title(room, giver) else {
+            continue;
+        };
+        let Some(receiver) = exchange_partner(room, giver) else {
+            continue;
+        };
+
+        let cards = best_cards(hands[giver], count, *room.tycoon().revolution.value());
+        give_cards(outbox, cards, giver, receiver);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PlayKind {
+    Set,      // Cards of the same rank, jokers can stand in for any rank
+    Sequence, // 3 or more consecutive ranks of the same suit, jokers can fill gaps
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Play {
+    kind: PlayKind,
+    size: u32,
+    rank: u8, // Rank of a set or the lowest rank of a sequence, NUM_RANKS for a set of only jokers
+}
+
+impl Play {
+    fn from_cards(cards: CardSet) -> Option<Play> {
+        if cards.is_empty() || !CardSet::FULL_DECK.contains(cards) {
+            return None;
+        }
+
+        let size = cards.len();
+        let mut ranks = cards.standard().ranks();
+
+        match (ranks.next(), ranks.next()) {
+            (None, _) => Some(Play {
+                kind: PlayKind::Set,
+                size,
+                rank: NUM_RANKS,
+            }),
+            (Some(rank), None) => Some(Play {
+                kind: PlayKind::Set,
+                size,
+                rank,
+            }),
+            // Anything else has to be a sequence in a single suit
+            _ if size < 3 => None,
+            _ => cards.sequence().map(|rank| Play {
+                kind: PlayKind::Sequence,
+                size,
+                rank,
+            }),
+        }
+    }
+
+    fn is_revolution(&self, rules: &TycoonRules) -> bool {
+        self.size >= 4 && (self.kind == PlayKind::Set || rules.sequence_revolution)
+    }
+
+    // Whether this play can be played on top of `other`, during a revolution lower ranks are stronger
+    fn beats(&self, other: &Play, reversed: bool) -> bool {
+        if self.kind != other.kind || self.size != other.size {
+            return false;
+        }
+
+        // Jokers on their own are always the strongest play
+        if other.rank == NUM_RANKS {
+            return false;
+        }
+        if self.rank == NUM_RANKS {
+            return true;
+        }
+
+        if reversed {
+            self.rank < other.rank
+        } else {
+            self.rank > other.rank
+        }
+    }
+}
+
+// Players are out of the round once they have finished
+fn is_active(room: &types::Room, player_index: usize) -> bool {
+    get_player(room, player_index)
+        .map(|player| player.tycoon().finish_position.value().is_none())
+        .unwrap_or_default()
+}
+
+fn next_active_player(room: &types::Room, player_index: usize) -> Option<usize> {
+    (1..=MAX_PLAYERS)
+        .map(|offset| (player_index + offset) % MAX_PLAYERS)
+        .find(|index| is_active(room, *index))
+}
+
+// Lowest position nobody has finished in yet, a fallen Tycoon takes last place early so this isn't always a count
+fn next_finish_position(room: &types::Room) -> u8 {
+    let taken: Vec<u8> = seated_players(room)
+        .filter_map(|index| *get_player(room, index)?.tycoon().finish_position.value())
+        .collect();
+
+    (0..MAX_PLAYERS as u8)
+        .find(|finish_position| !taken.contains(finish_position))
+        .unwrap_or_default()
+}
+
+fn is_reversed(room: &types::Room) -> bool {
+    *room.tycoon().revolution.value() != *room.tycoon().eleven_back.value()
+}
+
+fn clear_trick(room: &mut types::Room) {
+    room.tycoon_mut().last_played.set(CardSet::EMPTY);
+    room.tycoon_mut().eleven_back.set(false);
+}
+
+// Gives the lead to the player, or whoever is next if they have already gone out
+fn lead_from(room: &mut types::Room, player_index: usize) {
+    if is_active(room, player_index) {
+        room.tycoon_mut().turn.set(player_index as u8);
+    } else if let Some(next) = next_active_player(room, player_index) {
+        room.tycoon_mut().turn.set(next as u8);
+    }
+}
+
+// Someone other than the last Tycoon finished first, so the Tycoon is out of the round in last place
+fn capital_fall(room: &mut types::Room, winner: usize) {
+    let Some(tycoon) = find_title(room, Title::Tycoon) else {
+        return;
+    };
+
+    if tycoon == winner || !is_active(room, tycoon) {
+        return;
+    }
+
+    let last_place = seated_players(room).count().saturating_sub(1) as u8;
+    if let Some(player) = get_player_mut(room, tycoon) {
+        player.tycoon_mut().finish_position.set(Some(last_place));
+    }
+}
+
+// Moves the turn on after a pass, if it gets all the way back round to whoever played last, everyone else has
+// passed so the trick is cleared and they get to lead. If they have since gone out the next player leads instead.
+fn pass_turn(room: &mut types::Room) {
+    let turn = *room.tycoon().turn.value() as usize;
+    let last_played_player = room.tycoon().last_played_player as usize;
+
+    for offset in 1..=MAX_PLAYERS {
+        let index = (turn + offset) % MAX_PLAYERS;
+
+        if index == last_played_player {
+            clear_trick(room);
+            lead_from(room, index);
+            return;
+        }
+
+        if is_active(room, index) {
+            room.tycoon_mut().turn.set(index as u8);
+            return;
+        }
+    }
+}
+
+fn title_for(finish_position: u8, num_players: usize) -> Title {
+    let finish_position = finish_position as usize;
+
+    // With fewer than 4 players there is no Rich or Poor
+    match finish_position {
+        0 => Title::Tycoon,
+        _ if finish_position + 1 == num_players => Title::Beggar,
+        1 if num_players >= 4 => Title::Rich,
+        _ if num_players >= 4 && finish_position + 2 == num_players => Title::Poor,
+        _ => Title::Commoner,
+    }
+}
+
+fn points_for(title: Title) -> u16 {
+    match title {
+        Title::Tycoon => 4,
+        Title::Rich => 3,
+        Title::Commoner => 2,
+        Title::Poor => 1,
+        Title::Beggar => 0,
+    }
+}
+
+fn find_title(room: &types::Room, title: Title) -> Option<usize> {
+    seated_players(room).find(|index| {
+        get_player(room, *index)
+            .map(|player| *player.tycoon().title.value() == title)
+            .unwrap_or_default()
+    })
+}
+
+// The Tycoon and Beggar swap cards, as do the Rich and Poor
+fn exchange_partner(room: &types::Room, player_index: usize) -> Option<usize> {
+    let partner = match get_player(room, player_index)?.tycoon().title.value() {
+        Title::Tycoon => Title::Beggar,
+        Title::Beggar => Title::Tycoon,
+        Title::Rich => Title::Poor,
+        Title::Poor => Title::Rich,
+        Title::Commoner => return None,
+    };
+
+    find_title(room, partner)
+}
+
+// Number of cards a player chooses to give back during the exchange
+fn cards_owed(title: Title) -> u8 {
+    match title {
+        Title::Tycoon => 2,
+        Title::Rich => 1,
+        _ => 0,
+    }
+}
+
+// Jokers are always the best cards, after that it's the highest ranks unless there's a revolution
+fn best_cards(hand: CardSet, count: u32, revolution: bool) -> CardSet {
+    let standard: Vec<CardSet> = if revolution {
+        hand.standard().iter().collect()
+    } else {
+        hand.standard().iter().rev().collect()
+    };
+
+    hand.jokers()
+        .iter()
+        .rev()
+        .chain(standard)
+        .take(count as usize)
+        .collect()
+}
+
+fn give_cards(outbox: &mut Outbox, cards: CardSet, from: usize, to: usize) {
+    outbox.send_to::<TycoonRoom>(TycoonServerEvent::GiveCards { cards }, from);
+    outbox.send_to::<TycoonRoom>(TycoonServerEvent::ReceiveCards { cards }, to);
+}
+
+// The last player still holding cards finishes last, then everyone gets their title and points for the round
+fn end_round(room: &mut types::Room) {
+    let finish_position = next_finish_position(room);
+    let remaining: Vec<usize> = seated_players(room)
+        .filter(|index| is_active(room, *index))
+        .collect();
+
+    for index in remaining {
+        if let Some(player) = get_player_mut(room, index) {
+            player
+                .tycoon_mut()
+                .finish_position
+                .set(Some(finish_position));
+        }
+    }
+
+    let num_players = seated_players(room).count();
+    for index in seated_players(room).collect::<Vec<_>>() {
+        if let Some(player) = get_player_mut(room, index) {
+            let title = player
+                .tycoon()
+                .finish_position
+                .value()
+                .map(|finish_position| title_for(finish_position, num_players))
+                .unwrap_or_default();
+            player.tycoon_mut().title.set(title);
+
+            let points = *player.tycoon().points.value() + points_for(title);
+            player.tycoon_mut().points.set(points);
+        }
+    }
+
+    clear_trick(room);
+    room.tycoon_mut().state.set(RoomState::RoundOver);
+}
+
+fn is_players_turn(room: &types::Room, player_index: usize) -> bool {
+    *room.tycoon().state.value() == RoomState::Game
+        && *room.tycoon().turn.value() == player_index as u8
+}
@@ -27,6 +27,15 @@ pub fn num_players(room: &types::Room) -> usize {
         .count()
 }
 
+// Indices of every occupied seat, in seat order
+pub fn seated_players(room: &types::Room) -> impl Iterator<Item = usize> + '_ {
+    room.players
+        .iter()
+        .enumerate()
+        .filter(|(_, player)| player.value().is_some())
+        .map(|(index, _)| index)
+}
+
 // pub fn reset_room<T: GameLogic>(room: &mut T::Room) {
 
 // }
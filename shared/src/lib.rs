@@ -1,11 +1,17 @@
 pub mod games {
     pub mod carbo;
     pub mod coup;
+    #[allow(clippy::module_inception)]
     pub mod games;
     pub mod tycoon;
 }
 
+pub mod cards;
+pub mod fairness;
 mod helpers;
 pub mod logic;
+pub mod outbox;
+mod registry;
+pub mod shuffle;
 pub mod traits;
 pub mod types;
@@ -1,6 +1,9 @@
 use crate::{
-    games::{carbo, coup, tycoon},
-    helpers::{is_host, is_lobby},
+    fairness::{self, DealAudit},
+    games::games::game_config,
+    helpers::{is_host, is_lobby, num_players},
+    outbox::Audience,
+    shuffle::{self, GameRng, Seed},
     traits::{GameLogic, GameSignal, Networking},
     types::{self, ClientEvent, CommonClientEvent, CommonServerEvent, ServerEvent},
 };
@@ -11,16 +14,11 @@ pub fn handle_server_event(
     as_player: Option<usize>,
     is_server_side: bool,
 ) {
+    if !is_server_side {
+        room.deal_audit.record(event);
+    }
+
     match event {
-        ServerEvent::TycoonEvent(event) => {
-            tycoon::TycoonRoom::handle_server_game_event(room, event, as_player, is_server_side);
-        }
-        ServerEvent::CarboEvent(event) => {
-            carbo::CarboRoom::handle_server_game_event(room, event, as_player, is_server_side);
-        }
-        ServerEvent::CoupEvent(event) => {
-            coup::CoupRoom::handle_server_game_event(room, event, as_player, is_server_side);
-        }
         ServerEvent::CommonEvent(event) => {
             match event {
                 CommonServerEvent::HostChanged { player_index } => {
@@ -39,11 +37,11 @@ pub fn handle_server_event(
                     if player_index == room.host.value() {
                         let mut new_host = None;
                         for (index, player) in room.players.iter().enumerate() {
-                            if let Some(player) = player.value() {
-                                if !player.disconnected.value() {
-                                    new_host = Some(index as u8);
-                                    break;
-                                }
+                            if let Some(player) = player.value()
+                                && !player.disconnected.value()
+                            {
+                                new_host = Some(index as u8);
+                                break;
                             }
                         }
 
@@ -53,7 +51,10 @@ pub fn handle_server_event(
                     }
                 }
                 CommonServerEvent::PlayerJoined { name, player_index } => {
-                    let mut new_player = types::Player::default();
+                    let mut new_player = types::Player {
+                        game_player: room.game.value().new_player(),
+                        ..Default::default()
+                    };
                     new_player.name.set(*name);
                     room.players[*player_index as usize].set(Some(new_player));
                 }
@@ -62,10 +63,10 @@ pub fn handle_server_event(
                     //TODO: I think move all players after the player that left to the left
                 }
                 CommonServerEvent::PlayerReconnected { player_index } => {
-                    if let Some(player) = room.players.get_mut(*player_index as usize) {
-                        if let Some(player) = player.value_mut() {
-                            player.disconnected.set(false);
-                        }
+                    if let Some(player) = room.players.get_mut(*player_index as usize)
+                        && let Some(player) = player.value_mut()
+                    {
+                        player.disconnected.set(false);
                     }
                 }
                 CommonServerEvent::RoomJoined {
@@ -78,61 +79,65 @@ pub fn handle_server_event(
                     }
                 }
                 CommonServerEvent::NameChanged { player_index, name } => {
-                    if let Some(player) = room.players.get_mut(*player_index as usize) {
-                        if let Some(player) = player.value_mut() {
-                            player.name.set(*name);
-                        }
+                    if let Some(player) = room.players.get_mut(*player_index as usize)
+                        && let Some(player) = player.value_mut()
+                    {
+                        player.name.set(*name);
                     }
                 }
                 CommonServerEvent::GameChanged { game } => {
-                    room.game.set(*game);
+                    room.set_game(*game);
                 }
-                CommonServerEvent::ResetGame => {
-                    // TODO: this is a little verbose, maybe we can make a function to reset the game
-                    match room.game.value() {
-                        types::GameType::Carbo => {
-                            room.carbo = carbo::CarboRoom::default();
-                            room.players.iter_mut().for_each(|player| {
-                                if let Some(player) = player.value_mut() {
-                                    player.carbo = carbo::CarboPlayer::default();
-                                }
-                            });
-                        }
-                        types::GameType::Tycoon => {
-                            room.tycoon = tycoon::TycoonRoom::default();
-                            room.players.iter_mut().for_each(|player| {
-                                if let Some(player) = player.value_mut() {
-                                    player.tycoon = tycoon::TycoonPlayer::default();
-                                }
-                            });
-                        }
-                        types::GameType::Coup => {
-                            room.coup = coup::CoupRoom::default();
-                            room.players.iter_mut().for_each(|player| {
-                                if let Some(player) = player.value_mut() {
-                                    player.coup = coup::CoupPlayer::default();
-                                }
-                            });
-                        }
+                CommonServerEvent::SeedCommitted { commitment } => {
+                    if !is_server_side {
+                        room.deal_audit = DealAudit::new(*commitment, room);
+                        room.deal_verified.set(None);
+                    }
+                }
+                CommonServerEvent::SeedRevealed { seed, nonce } => {
+                    if !is_server_side {
+                        let player_index = *room.player_index.value() as usize;
+                        let verified = room.deal_audit.verify(*seed, nonce, player_index);
+                        room.deal_verified.set(verified);
+                        room.deal_audit = DealAudit::default();
                     }
                 }
+                CommonServerEvent::ResetGame => {
+                    room.reset_game();
+                }
             }
         }
         ServerEvent::Unknown => {} // TODO: Either ignore or force the client to disconnect
+        event => event.handle_game_event(room, as_player, is_server_side),
     }
 }
 
+// The room as the player is allowed to see it, for sending them the whole room when they join
+pub fn room_snapshot(room: &types::Room, player_index: usize) -> types::Room {
+    let mut snapshot = room.detached_copy();
+    let game = *snapshot.game.value();
+    game.hide_private_state(&mut snapshot, player_index);
+    snapshot
+}
+
+// How long the server should wait before timing out whatever the current game is waiting on, if anything
+pub fn response_deadline(room: &types::Room) -> Option<(u16, u8)> {
+    room.game.value().response_deadline(room)
+}
+
+pub fn handle_response_timeout(
+    room: &mut types::Room,
+    connections: &mut impl Networking,
+    window: u16,
+) {
+    let events = with_rng(room, |room, rng| {
+        room.game.value().handle_response_timeout(room, window, rng)
+    });
+    connections.deliver(room, events);
+}
+
 pub fn validate_client_event(room: &types::Room, event: &ClientEvent, player_index: usize) -> bool {
     match event {
-        ClientEvent::TycoonEvent(event) => {
-            tycoon::TycoonRoom::validate_client_game_event(room, event, player_index)
-        }
-        ClientEvent::CarboEvent(event) => {
-            carbo::CarboRoom::validate_client_game_event(room, event, player_index)
-        }
-        ClientEvent::CoupEvent(event) => {
-            coup::CoupRoom::validate_client_game_event(room, event, player_index)
-        }
         ClientEvent::CommonEvent(event) => {
             match event {
                 CommonClientEvent::LeaveRoom => true,
@@ -147,21 +152,16 @@ pub fn validate_client_event(room: &types::Room, event: &ClientEvent, player_ind
                         return false;
                     }
 
-                    match room.game.value() {
-                        types::GameType::Carbo => {
-                            coup::CoupRoom::validate_start_game(room, player_index)
-                        }
-                        types::GameType::Tycoon => {
-                            tycoon::TycoonRoom::validate_start_game(room, player_index)
-                        }
-                        types::GameType::Coup => {
-                            coup::CoupRoom::validate_start_game(room, player_index)
-                        }
-                    }
+                    let config = game_config(*room.game.value());
+                    let num_players = num_players(room) as u16;
+                    num_players >= config.min_players
+                        && num_players <= config.max_players
+                        && (config.validate_start_game)(room, player_index)
                 }
             }
         }
         ClientEvent::Unknown => false,
+        event => event.validate_game_event(room, player_index),
     }
 }
 
@@ -179,16 +179,6 @@ pub fn handle_client_event(
     player_index: usize,
 ) {
     match event {
-        //TODO: Ignore events that are not for the current game
-        ClientEvent::TycoonEvent(event) => {
-            tycoon::TycoonRoom::handle_client_game_event(room, event, connections, player_index);
-        }
-        ClientEvent::CarboEvent(event) => {
-            carbo::CarboRoom::handle_client_game_event(room, event, connections, player_index);
-        }
-        ClientEvent::CoupEvent(event) => {
-            coup::CoupRoom::handle_client_game_event(room, event, connections, player_index);
-        }
         ClientEvent::CommonEvent(event) => match event {
             CommonClientEvent::LeaveRoom => {
                 connections.send_to_all_except_origin(
@@ -232,22 +222,80 @@ pub fn handle_client_event(
                     player_index,
                 );
             }
-            CommonClientEvent::StartGame => match room.game.value() {
-                types::GameType::Carbo => {
-                    carbo::CarboRoom::handle_start_game(room, connections);
-                }
-                types::GameType::Tycoon => {
-                    tycoon::TycoonRoom::handle_start_game(room, connections);
-                }
-                types::GameType::Coup => {
-                    coup::CoupRoom::handle_start_game(room, connections);
-                }
-            },
+            CommonClientEvent::StartGame => {
+                start_game(room, connections, shuffle::new_seed());
+            }
         },
         ClientEvent::Unknown => {}
+        //TODO: Ignore events that are not for the current game
+        event => event.handle_game_event(room, connections, player_index),
     }
 }
 
-// Note to future tyler: I think I can make this a macro.
-// Additionally, I would like to remove the mutability of the room and connections, instead returning a list of events to send
-// in order, this way I can't accidently edit the room or connections here when it really should be done in the handle_client_event
+// Applies an event to the server's room. Outboxes go through this too, so their preview of the room always matches
+// what delivering the events leaves behind. If the event ended the game this returns the seed reveal to send after
+// it, the seed can't be used to predict anything anymore. Checked here so every way a game can end reveals it,
+// whether it came from a player, a timeout or a disconnect
+pub(crate) fn handle_server_side_event(
+    room: &mut types::Room,
+    event: &ServerEvent,
+    audience: Audience,
+) -> Option<ServerEvent> {
+    let was_in_game = !is_lobby(room);
+    handle_server_event(room, event, audience.as_player(), true);
+
+    (was_in_game && is_lobby(room)).then(|| {
+        ServerEvent::CommonEvent(CommonServerEvent::SeedRevealed {
+            seed: room.rng.seed(),
+            nonce: room.rng.nonce(),
+        })
+    })
+}
+
+// Reseeds the room's rng before dealing, so starting a game with the same seed always gives the same deal. Players
+// are sent a commitment to the seed first so they can check the deal wasn't rigged once the game is over
+pub fn start_game(room: &mut types::Room, connections: &mut impl Networking, seed: Seed) {
+    room.rng = GameRng::new(seed, rand::random());
+
+    let commitment = fairness::commitment(seed, &room.rng.nonce());
+    connections.send_to_all(
+        room,
+        ServerEvent::CommonEvent(CommonServerEvent::SeedCommitted { commitment }),
+    );
+
+    let events = deal(room);
+    connections.deliver(room, events);
+}
+
+pub(crate) fn deal(room: &mut types::Room) -> Vec<(Audience, ServerEvent)> {
+    let game = *room.game.value();
+    game.handle_start_game(room)
+}
+
+// Game handlers can only read the room, they send events back for the connections to deliver and apply
+pub(crate) fn handle_client_game_event<Logic: GameLogic>(
+    room: &mut types::Room,
+    event: &Logic::GameClientEvent,
+    connections: &mut impl Networking,
+    player_index: usize,
+) {
+    if !connections.is_server_side() && Logic::is_server_only(event) {
+        return;
+    }
+
+    let events = with_rng(room, |room, rng| {
+        Logic::handle_client_game_event(room, event, player_index, rng)
+    });
+    connections.deliver(room, events);
+}
+
+// Lends out the room's rng alongside a read only room, for handlers that need to shuffle or draw
+pub fn with_rng<T>(
+    room: &mut types::Room,
+    handle: impl FnOnce(&types::Room, &mut GameRng) -> T,
+) -> T {
+    let mut rng = std::mem::take(&mut room.rng);
+    let result = handle(room, &mut rng);
+    room.rng = rng;
+    result
+}
@@ -0,0 +1,94 @@
+use crate::{
+    logic,
+    traits::GameLogic,
+    types::{self, ServerEvent},
+};
+
+// Who a server event is for, the Networking implementations decide how to deliver and apply it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Audience {
+    All,
+    AllExcept(usize),
+    Player(usize),
+    // Used for deterministic events where the origin client can handle the event instantly
+    AllExceptOrigin(usize),
+}
+
+impl Audience {
+    pub fn includes(&self, player_index: usize) -> bool {
+        match self {
+            Audience::All => true,
+            Audience::AllExcept(except) | Audience::AllExceptOrigin(except) => {
+                *except != player_index
+            }
+            Audience::Player(player) => *player == player_index,
+        }
+    }
+
+    // Events for a single player are applied as that player, so the server keeps whatever only they were told
+    pub fn as_player(&self) -> Option<usize> {
+        match self {
+            Audience::Player(player_index) => Some(*player_index),
+            _ => None,
+        }
+    }
+}
+
+// Collects the events a game handler wants to send, in order. Every event is also applied to a copy of the room
+// the same way the server will apply it, so a handler can look at the state earlier events leave behind (like
+// whether a round is over) without ever being able to change the real room itself.
+pub struct Outbox {
+    room: types::Room,
+    events: Vec<(Audience, ServerEvent)>,
+}
+
+impl Outbox {
+    pub fn new(room: &types::Room) -> Self {
+        Self {
+            room: room.detached_copy(),
+            events: Vec::new(),
+        }
+    }
+
+    // The room as it will be once every event so far has been applied
+    pub fn room(&self) -> &types::Room {
+        &self.room
+    }
+
+    pub fn send(&mut self, audience: Audience, event: ServerEvent) {
+        // The seed reveal is left for delivery to send, the preview only needs the state
+        logic::handle_server_side_event(&mut self.room, &event, audience);
+
+        self.events.push((audience, event));
+    }
+
+    pub fn send_to_all<Logic: GameLogic>(&mut self, event: Logic::GameServerEvent) {
+        self.send(Audience::All, Logic::wrap_game_event(event));
+    }
+
+    pub fn send_to<Logic: GameLogic>(
+        &mut self,
+        event: Logic::GameServerEvent,
+        player_index: usize,
+    ) {
+        self.send(
+            Audience::Player(player_index),
+            Logic::wrap_game_event(event),
+        );
+    }
+
+    pub fn send_to_all_except_origin<Logic: GameLogic>(
+        &mut self,
+        event: Logic::GameServerEvent,
+        origin: usize,
+    ) {
+        self.send(
+            Audience::AllExceptOrigin(origin),
+            Logic::wrap_game_event(event),
+        );
+    }
+
+    pub fn into_events(self) -> Vec<(Audience, ServerEvent)> {
+        self.events
+    }
+}
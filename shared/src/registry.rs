@@ -0,0 +1,283 @@
+// Generates everything that has to list every game from a single list of GameLogic implementors. Each entry names
+// the Room and Player accessors for the game's state, its GameLogic type, its GameType variant and the variant
+// wrapping its events in ServerEvent and ClientEvent:
+//
+//     accessor, accessor_mut: path::ToGameLogic => GameTypeVariant, EventVariant,
+//
+// Attributes on an entry go on its GameType variant, like #[default]. The common Room and Player fields are written
+// as normal structs inside the macro, the active game's state is added after them as `game_room` and `game_player`.
+// Besides the types this generates the dispatch logic.rs uses to reach a game without naming it, and each game's
+// GameConfig.
+macro_rules! register_games {
+    (
+        games {
+            $($(#[$game_meta:meta])* $field:ident, $field_mut:ident: $logic:path => $game:ident, $event:ident,)*
+        }
+
+        $(#[$room_meta:meta])*
+        pub struct Room {
+            $($room_fields:tt)*
+        }
+
+        $(#[$player_meta:meta])*
+        pub struct Player {
+            $($player_fields:tt)*
+        }
+    ) => {
+        //TODO: Consider implementing GameLogic for this directly
+        #[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Debug, Eq, Hash)]
+        pub enum GameType {
+            $($(#[$game_meta])* $game,)*
+        }
+
+        $(#[$room_meta])*
+        pub struct Room {
+            $($room_fields)*
+            pub game_room: GameRoom,
+        }
+
+        $(#[$player_meta])*
+        pub struct Player {
+            $($player_fields)*
+            pub game_player: GamePlayer,
+        }
+
+        // Only the active game's state is kept, so rooms and join messages don't carry every other game's state too
+        #[derive(Serialize, Deserialize, Clone, Debug)]
+        #[allow(clippy::large_enum_variant)] // Suppresses only this warning, a room only ever holds one of these
+        pub enum GameRoom {
+            $($game(<$logic as $crate::traits::GameLogic>::Room),)*
+        }
+
+        #[derive(Serialize, Deserialize, Clone, Debug)]
+        pub enum GamePlayer {
+            $($game(<$logic as $crate::traits::GameLogic>::Player),)*
+        }
+
+        impl Default for GameRoom {
+            fn default() -> Self {
+                GameType::default().new_room()
+            }
+        }
+
+        impl Default for GamePlayer {
+            fn default() -> Self {
+                GameType::default().new_player()
+            }
+        }
+
+        // Anything that can run while another game is active, like the frontend's components, should use these
+        #[allow(unreachable_patterns)]
+        impl GameRoom {
+            $(
+                pub fn $field(&self) -> Option<&<$logic as $crate::traits::GameLogic>::Room> {
+                    match self {
+                        GameRoom::$game(room) => Some(room),
+                        _ => None,
+                    }
+                }
+
+                pub fn $field_mut(&mut self) -> Option<&mut <$logic as $crate::traits::GameLogic>::Room> {
+                    match self {
+                        GameRoom::$game(room) => Some(room),
+                        _ => None,
+                    }
+                }
+            )*
+        }
+
+        #[allow(unreachable_patterns)]
+        impl GamePlayer {
+            $(
+                pub fn $field(&self) -> Option<&<$logic as $crate::traits::GameLogic>::Player> {
+                    match self {
+                        GamePlayer::$game(player) => Some(player),
+                        _ => None,
+                    }
+                }
+
+                pub fn $field_mut(&mut self) -> Option<&mut <$logic as $crate::traits::GameLogic>::Player> {
+                    match self {
+                        GamePlayer::$game(player) => Some(player),
+                        _ => None,
+                    }
+                }
+            )*
+        }
+
+        // Game logic is only ever dispatched to the active game, so these panic if asked for any other game's state
+        impl Room {
+            $(
+                pub fn $field(&self) -> &<$logic as $crate::traits::GameLogic>::Room {
+                    self.game_room
+                        .$field()
+                        .unwrap_or_else(|| panic!("{:?} isn't the active game", GameType::$game))
+                }
+
+                pub fn $field_mut(&mut self) -> &mut <$logic as $crate::traits::GameLogic>::Room {
+                    self.game_room
+                        .$field_mut()
+                        .unwrap_or_else(|| panic!("{:?} isn't the active game", GameType::$game))
+                }
+            )*
+        }
+
+        impl Player {
+            $(
+                pub fn $field(&self) -> &<$logic as $crate::traits::GameLogic>::Player {
+                    self.game_player
+                        .$field()
+                        .unwrap_or_else(|| panic!("{:?} isn't the active game", GameType::$game))
+                }
+
+                pub fn $field_mut(&mut self) -> &mut <$logic as $crate::traits::GameLogic>::Player {
+                    self.game_player
+                        .$field_mut()
+                        .unwrap_or_else(|| panic!("{:?} isn't the active game", GameType::$game))
+                }
+            )*
+        }
+
+        #[derive(Default, Serialize, Deserialize, Clone, Debug)]
+        #[allow(clippy::large_enum_variant)] // Suppresses only this warning, TODO: More investigation on if Box is worth it since
+        // this isn't stored and is just passed around as a reference
+        pub enum ServerEvent {
+            CommonEvent(CommonServerEvent),
+            $($event(<$logic as $crate::traits::GameLogic>::GameServerEvent),)*
+
+            #[default]
+            Unknown,
+        }
+
+        #[derive(Default, Serialize, Deserialize, Clone, Debug)]
+        pub enum ClientEvent {
+            CommonEvent(CommonClientEvent),
+            $($event(<$logic as $crate::traits::GameLogic>::GameClientEvent),)*
+
+            #[default]
+            Unknown,
+        }
+
+        impl ServerEvent {
+            // Common events are handled by logic::handle_server_event itself, events for any game but the active one
+            // are ignored since there's no state for them to change
+            pub(crate) fn handle_game_event(
+                &self,
+                room: &mut Room,
+                as_player: Option<usize>,
+                is_server_side: bool,
+            ) {
+                match self {
+                    $(ServerEvent::$event(event) if *room.game.value() == GameType::$game => {
+                        <$logic as $crate::traits::GameLogic>::handle_server_game_event(
+                            room,
+                            event,
+                            as_player,
+                            is_server_side,
+                        );
+                    })*
+                    _ => {}
+                }
+            }
+        }
+
+        impl ClientEvent {
+            pub(crate) fn validate_game_event(&self, room: &Room, player_index: usize) -> bool {
+                match self {
+                    $(ClientEvent::$event(event) if *room.game.value() == GameType::$game => {
+                        <$logic as $crate::traits::GameLogic>::validate_client_game_event(
+                            room,
+                            event,
+                            player_index,
+                        )
+                    })*
+                    _ => false,
+                }
+            }
+
+            pub(crate) fn handle_game_event(
+                &self,
+                room: &mut Room,
+                connections: &mut impl $crate::traits::Networking,
+                player_index: usize,
+            ) {
+                match self {
+                    $(ClientEvent::$event(event) if *room.game.value() == GameType::$game => {
+                        $crate::logic::handle_client_game_event::<$logic>(
+                            room,
+                            event,
+                            connections,
+                            player_index,
+                        );
+                    })*
+                    _ => {}
+                }
+            }
+        }
+
+        impl GameType {
+            // Every game, in the order they were registered
+            pub const ALL: [GameType; [$(GameType::$game),*].len()] = [$(GameType::$game),*];
+
+            pub(crate) const fn config(self) -> $crate::games::games::GameConfig {
+                match self {
+                    $(GameType::$game => $crate::games::games::config::<$logic>(self),)*
+                }
+            }
+
+            pub(crate) fn response_deadline(self, room: &Room) -> Option<(u16, u8)> {
+                match self {
+                    $(GameType::$game => {
+                        <$logic as $crate::traits::GameLogic>::response_deadline(room)
+                    })*
+                }
+            }
+
+            pub(crate) fn handle_response_timeout(
+                self,
+                room: &Room,
+                window: u16,
+                rng: &mut $crate::shuffle::GameRng,
+            ) -> Vec<($crate::outbox::Audience, ServerEvent)> {
+                match self {
+                    $(GameType::$game => {
+                        <$logic as $crate::traits::GameLogic>::handle_response_timeout(room, window, rng)
+                    })*
+                }
+            }
+
+            pub(crate) fn hide_private_state(self, room: &mut Room, player_index: usize) {
+                match self {
+                    $(GameType::$game => {
+                        <$logic as $crate::traits::GameLogic>::hide_private_state(room, player_index)
+                    })*
+                }
+            }
+
+            pub(crate) fn handle_start_game(
+                self,
+                room: &mut Room,
+            ) -> Vec<($crate::outbox::Audience, ServerEvent)> {
+                match self {
+                    $(GameType::$game => {
+                        <$logic as $crate::traits::GameLogic>::handle_start_game(room)
+                    })*
+                }
+            }
+
+            pub fn new_room(self) -> GameRoom {
+                match self {
+                    $(GameType::$game => GameRoom::$game(Default::default()),)*
+                }
+            }
+
+            pub fn new_player(self) -> GamePlayer {
+                match self {
+                    $(GameType::$game => GamePlayer::$game(Default::default()),)*
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use register_games;
@@ -0,0 +1,59 @@
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::fairness::Nonce;
+
+// Every shuffle and random choice in a game comes from a single rng that's seeded when the game starts, so the
+// same seed and the same player events always play out the same game. The backend logs the seed of each game so
+// tests and bug reports can reproduce exactly what the players saw.
+pub type Seed = u64;
+
+pub fn new_seed() -> Seed {
+    rand::random()
+}
+
+#[derive(Clone, Debug)]
+pub struct GameRng {
+    seed: Seed,
+    nonce: Nonce, // Kept with the seed so the seed's commitment can be checked once it's revealed
+    rng: ChaCha8Rng,
+}
+
+impl GameRng {
+    pub fn new(seed: Seed, nonce: Nonce) -> Self {
+        Self {
+            seed,
+            nonce,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> Seed {
+        self.seed
+    }
+
+    pub fn nonce(&self) -> Nonce {
+        self.nonce
+    }
+}
+
+// Only used until the first game starts, every game reseeds the room's rng
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(0, Nonce::default())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.rng.fill_bytes(dst)
+    }
+}
@@ -1,8 +1,9 @@
 use serde::{Serialize, de::DeserializeOwned};
 
 use crate::{
-    helpers::*,
     logic::{self, handle_server_event},
+    outbox::Audience,
+    shuffle::GameRng,
     types::{self, MAX_PLAYERS},
 };
 
@@ -12,18 +13,29 @@ pub trait GameLogic {
     type Room: Serialize + DeserializeOwned + Clone + Default;
     type Player: Serialize + DeserializeOwned + Clone + Default;
 
+    const MIN_PLAYERS: u16;
+    const MAX_PLAYERS: u16;
+
     // Maybe in the future ill make it so that the room is the Room type of the logic
     fn validate_client_game_event(
         room: &types::Room,
         event: &Self::GameClientEvent,
         player_index: usize,
     ) -> bool;
+    // Handlers only read the room and return the events to send in order, the Networking implementations then
+    // deliver and apply them. Use an Outbox to build the events when a handler needs to see their effect on the
+    // room, and the rng for anything random
     fn handle_client_game_event(
-        room: &mut types::Room,
+        room: &types::Room,
         event: &Self::GameClientEvent,
-        connections: &mut impl Networking,
         player_index: usize,
-    );
+        rng: &mut GameRng,
+    ) -> Vec<(Audience, types::ServerEvent)>;
+    // Events whose outcome depends on state only the server has, like drawing from the deck. Clients skip these
+    // instead of predicting them and wait for the server's events
+    fn is_server_only(_event: &Self::GameClientEvent) -> bool {
+        false
+    }
     fn handle_server_game_event(
         room: &mut types::Room,
         event: &Self::GameServerEvent,
@@ -32,10 +44,30 @@ pub trait GameLogic {
     );
 
     // This should only validate the requirements for the current game type
-    // things like is_lobby and is_host are generically handled.
+    // things like is_lobby, is_host and the player count are generically handled.
     fn validate_start_game(room: &types::Room, player_index: usize) -> bool;
 
-    fn handle_start_game(room: &mut types::Room, connections: &mut impl Networking);
+    // Any shuffling should use room.rng, which has already been seeded for the new game. This is the one place a
+    // game can change the room directly, to set up state only the server knows like the deck
+    fn handle_start_game(room: &mut types::Room) -> Vec<(Audience, types::ServerEvent)>;
+
+    // Whole rooms are sent to players joining mid-game, so anything only the server or another player should know
+    // has to be cleared out of the copy first, like the deck or other players' hands
+    fn hide_private_state(room: &mut types::Room, player_index: usize);
+
+    // Games that give players a time limit to respond return how long the server should wait, along with the response
+    // window it's for so handle_response_timeout can tell if that window has already closed
+    fn response_deadline(_: &types::Room) -> Option<(u16, u8)> {
+        None
+    }
+
+    fn handle_response_timeout(
+        _: &types::Room,
+        _: u16,
+        _: &mut GameRng,
+    ) -> Vec<(Audience, types::ServerEvent)> {
+        Vec::new()
+    }
 
     fn wrap_game_event(event: Self::GameServerEvent) -> types::ServerEvent;
 }
@@ -59,23 +91,23 @@ where
 }
 
 pub trait Networking {
-    fn send_to_all_game_event<Logic: GameLogic>(
-        &mut self,
-        room: &mut types::Room,
-        event: Logic::GameServerEvent,
-    ) {
-        self.send_to_all(room, Logic::wrap_game_event(event));
+    fn is_server_side(&self) -> bool;
+
+    fn deliver(&mut self, room: &mut types::Room, events: Vec<(Audience, types::ServerEvent)>) {
+        for (audience, event) in events {
+            match audience {
+                Audience::All => self.send_to_all(room, event),
+                Audience::AllExcept(except) => self.send_to_all_except(room, event, except),
+                Audience::Player(player_index) => self.send_to(room, event, player_index),
+                Audience::AllExceptOrigin(origin) => {
+                    self.send_to_all_except_origin(room, event, origin)
+                }
+            }
+        }
     }
+
     fn send_to_all(&mut self, room: &mut types::Room, event: types::ServerEvent);
 
-    fn send_to_all_except_game_event<Logic: GameLogic>(
-        &mut self,
-        room: &mut types::Room,
-        event: Logic::GameServerEvent,
-        except: usize,
-    ) {
-        self.send_to_all_except(room, Logic::wrap_game_event(event), except);
-    }
     fn send_to_all_except(
         &mut self,
         room: &mut types::Room,
@@ -83,25 +115,9 @@ pub trait Networking {
         except: usize,
     );
 
-    fn send_to_game_event<Logic: GameLogic>(
-        &mut self,
-        room: &mut types::Room,
-        event: Logic::GameServerEvent,
-        player_index: usize,
-    ) {
-        self.send_to(room, Logic::wrap_game_event(event), player_index);
-    }
     fn send_to(&mut self, room: &mut types::Room, event: types::ServerEvent, player_index: usize);
 
     // Used for deterministic events where the origin client can handle the event instantly
-    fn send_to_all_except_origin_game_event<Logic: GameLogic>(
-        &mut self,
-        room: &mut types::Room,
-        event: Logic::GameServerEvent,
-        origin: usize,
-    ) {
-        self.send_to_all_except_origin(room, Logic::wrap_game_event(event), origin);
-    }
     fn send_to_all_except_origin(
         &mut self,
         room: &mut types::Room,
@@ -118,14 +134,22 @@ impl<T> Networking for [Option<T>; MAX_PLAYERS]
 where
     T: NetworkingSend,
 {
+    fn is_server_side(&self) -> bool {
+        true
+    }
+
     fn send_to_all(&mut self, room: &mut types::Room, event: types::ServerEvent) {
-        logic::handle_server_event(room, &event, None, true);
+        let revealed = logic::handle_server_side_event(room, &event, Audience::All);
 
         println!("Sending {:?} to all", event);
 
         for connection in self.iter_mut().flatten() {
             connection.send(&event);
         }
+
+        if let Some(revealed) = revealed {
+            self.send_to_all(room, revealed);
+        }
     }
 
     fn send_to_all_except(
@@ -134,30 +158,40 @@ where
         event: types::ServerEvent,
         except: usize,
     ) {
-        logic::handle_server_event(room, &event, None, true);
+        let revealed = logic::handle_server_side_event(room, &event, Audience::AllExcept(except));
 
         println!("Sending {:?} to all except {}", event, except);
 
         for (index, connection) in self.iter_mut().enumerate() {
-            if index != except {
-                if let Some(connection) = connection {
-                    connection.send(&event);
-                }
+            if index != except
+                && let Some(connection) = connection
+            {
+                connection.send(&event);
             }
         }
+
+        if let Some(revealed) = revealed {
+            self.send_to_all(room, revealed);
+        }
     }
 
     fn send_to(&mut self, room: &mut types::Room, event: types::ServerEvent, player_index: usize) {
+        // Handled even if the player has left, otherwise the server would lose whatever the event changes, like the
+        // card a player drew to replace a revealed one
+        let revealed =
+            logic::handle_server_side_event(room, &event, Audience::Player(player_index));
+
         println!("Sending {:?} to {}", event, player_index);
 
         if let Some(Some(connection)) = self.get_mut(player_index) {
             connection.send(&event);
         } else {
             println!("Tried to send to a connection that doesn't exist");
-            return;
         }
 
-        logic::handle_server_event(room, &event, Some(player_index), true); // Only need to handle the event if we actually sent it to a player
+        if let Some(revealed) = revealed {
+            self.send_to_all(room, revealed);
+        }
     }
 
     fn send_to_all_except_origin(
@@ -175,6 +209,10 @@ where
 // This is useful is alot of games for instant feedback, for example, in a card game like tycoon millionaire, the outcome
 // of playing a card is deterministic and the client can update its state instantly without waiting for the server
 impl Networking for types::ClientConnection {
+    fn is_server_side(&self) -> bool {
+        false
+    }
+
     fn send_to_all(&mut self, _room: &mut types::Room, _event: types::ServerEvent) {} // Do nothing
     fn send_to_all_except(
         &mut self,
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use crate::fairness::{Commitment, DealAudit, Nonce};
 use crate::games::{carbo, coup, tycoon};
-use crate::traits::{self, GameSignal};
+use crate::registry::register_games;
+use crate::shuffle::{GameRng, Seed};
+use crate::traits::GameSignal;
 
 pub const MAX_PLAYERS: usize = 8;
 pub const MAX_NAME_LENGTH: usize = 20;
@@ -9,16 +12,6 @@ pub const MAX_NAME_LENGTH: usize = 20;
 #[derive(Default, Clone, Copy, Debug)]
 pub struct ClientConnection;
 
-//TODO: Consider implementing GameLogic for this directly
-#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Debug, Eq, Hash)]
-pub enum GameType {
-    Tycoon,
-    Carbo,
-
-    #[default]
-    Coup,
-}
-
 #[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Debug)]
 pub enum RoomState {
     #[default]
@@ -26,48 +19,83 @@ pub enum RoomState {
     InGame,
 }
 
-//TODO: Investigate if we can ever add Copy back to this
-#[derive(Default, Serialize, Deserialize, Clone, Debug)]
-pub struct Room {
-    pub state: SignalType<RoomState>,
-    pub game: SignalType<GameType>,
-    pub host: SignalType<u8>,
-    pub player_index: SignalType<u8>, // TODO: If we ever make spectating possible, this will need to be a SignalType<Option<u8>>
-    pub carbo: carbo::CarboRoom,
-    pub tycoon: tycoon::TycoonRoom,
-    pub coup: coup::CoupRoom,
-    pub players: [SignalType<Option<Player>>; MAX_PLAYERS],
+// GameType, the game state, the event variants and the dispatch to each game are all generated from this list, see
+// registry.rs. Adding a game means writing its GameLogic under games/, adding it here and giving it a component in
+// the frontend's components/game.rs, which won't compile until it does
+register_games! {
+    games {
+        tycoon, tycoon_mut: tycoon::TycoonRoom => Tycoon, TycoonEvent,
+        carbo, carbo_mut: carbo::CarboRoom => Carbo, CarboEvent,
+        #[default]
+        coup, coup_mut: coup::CoupRoom => Coup, CoupEvent,
+    }
+
+    //TODO: Investigate if we can ever add Copy back to this
+    #[derive(Default, Serialize, Deserialize, Clone, Debug)]
+    pub struct Room {
+        pub state: SignalType<RoomState>,
+        pub game: SignalType<GameType>,
+        pub host: SignalType<u8>,
+        pub player_index: SignalType<u8>, // TODO: If we ever make spectating possible, this will need to be a SignalType<Option<u8>>
+        pub players: [SignalType<Option<Player>>; MAX_PLAYERS],
+
+        // Never sent to clients, anyone with the rng's state could predict every shuffle for the rest of the game
+        #[serde(skip)]
+        pub rng: GameRng,
+
+        // Client side only, used to check the opening deal of the current game once the server reveals the seed
+        #[serde(skip)]
+        pub deal_audit: DealAudit,
+        #[serde(skip)]
+        pub deal_verified: SignalType<Option<bool>>, // None until a game this client saw dealt has ended
+    }
+
+    #[derive(Default, Serialize, Deserialize, Clone, Debug)]
+    pub struct Player {
+        pub name: SignalType<[u8; MAX_NAME_LENGTH]>,
+        pub disconnected: SignalType<bool>,
+    }
 }
 
-#[derive(Default, Serialize, Deserialize, Clone, Debug)]
-pub struct Player {
-    pub name: SignalType<[u8; MAX_NAME_LENGTH]>,
-    pub disconnected: SignalType<bool>,
-    pub carbo: carbo::CarboPlayer,
-    pub tycoon: tycoon::TycoonPlayer,
-    pub coup: coup::CoupPlayer,
+impl Room {
+    // A copy that can be changed without touching this room. Cloning a room on the frontend would share its
+    // signals, so the copy is rebuilt from its bytes instead
+    pub(crate) fn detached_copy(&self) -> Room {
+        #[cfg(feature = "frontend")]
+        {
+            use crate::traits::ToFromBytes;
+            Room::from_bytes(&self.to_bytes())
+        }
+        #[cfg(not(feature = "frontend"))]
+        self.clone()
+    }
+
+    // Swaps in fresh state for the new game, choosing the game that's already active keeps its state
+    pub fn set_game(&mut self, game: GameType) {
+        if *self.game.value() == game {
+            return;
+        }
+
+        self.game.set(game);
+        self.reset_game();
+    }
+
+    // Puts the active game's room and player state back to how it was before the game started
+    pub fn reset_game(&mut self) {
+        let game = *self.game.value();
+        self.game_room = game.new_room();
+        self.players.iter_mut().for_each(|player| {
+            if let Some(player) = player.value_mut() {
+                player.game_player = game.new_player();
+            }
+        });
+    }
 }
 
 //
 // Event types
 //
 
-// TODO: Use a macro to generate the client events
-// If I use the form <game::GameRoom as GameLogic>::GameServerEvent, I can probably write a macro to generate the
-// Room, Player and Event types rather then having to update each one each time I add a new game
-#[derive(Default, Serialize, Deserialize, Clone, Debug)]
-#[allow(clippy::large_enum_variant)] // Suppresses only this warning, TODO: More investigation on if Box is worth it since
-// this isn't stored and is just passed around as a reference
-pub enum ServerEvent {
-    CommonEvent(CommonServerEvent),
-    CarboEvent(<carbo::CarboRoom as traits::GameLogic>::GameServerEvent),
-    TycoonEvent(<tycoon::TycoonRoom as traits::GameLogic>::GameServerEvent),
-    CoupEvent(<coup::CoupRoom as traits::GameLogic>::GameServerEvent),
-
-    #[default]
-    Unknown,
-}
-
 #[derive(Serialize, Deserialize, Clone, Debug)] //TODO: Clean up derives
 #[allow(clippy::large_enum_variant)] // Suppresses only this warning
 pub enum CommonServerEvent {
@@ -99,18 +127,14 @@ pub enum CommonServerEvent {
         game: GameType,
     },
     ResetGame,
-}
-
-// TODO: Use a macro to generate the client events
-#[derive(Default, Serialize, Deserialize, Clone, Debug)]
-pub enum ClientEvent {
-    CommonEvent(CommonClientEvent),
-    CarboEvent(<carbo::CarboRoom as traits::GameLogic>::GameClientEvent),
-    TycoonEvent(<tycoon::TycoonRoom as traits::GameLogic>::GameClientEvent),
-    CoupEvent(<coup::CoupRoom as traits::GameLogic>::GameClientEvent),
-
-    #[default]
-    Unknown,
+    // Sent before a game is dealt and once it's over, see fairness.rs
+    SeedCommitted {
+        commitment: Commitment,
+    },
+    SeedRevealed {
+        seed: Seed,
+        nonce: Nonce,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -149,6 +173,16 @@ impl<T: Clone + 'static> SignalType<T> {
     }
 }
 
+impl<T: Clone> SignalType<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: value.clone(),
+            #[cfg(feature = "frontend")]
+            signal: ArcRwSignal::new(value),
+        }
+    }
+}
+
 impl<T: Clone + 'static> GameSignal<T> for SignalType<T> {
     fn value(&self) -> &T {
         &self.value
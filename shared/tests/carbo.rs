@@ -0,0 +1,181 @@
+mod common;
+
+use common::{Connections, cards};
+use shared::{
+    cards::CardSet,
+    games::carbo::{CarboClientEvent, CarboRoomState, HAND_SIZE, LARGE_TABLE_HAND_SIZE},
+    logic::validate_client_event,
+    traits::GameSignal,
+    types::{ClientEvent, CommonClientEvent, GameType, Player, Room, RoomState},
+};
+
+fn player(room: &Room, player_index: usize) -> &Player {
+    room.players[player_index].value().as_ref().unwrap()
+}
+
+fn hand(room: &Room, player_index: usize) -> CardSet {
+    *player(room, player_index).carbo().visible_cards.value()
+}
+
+// A game of 3 where it's player 0's turn with `hand` to play onto `top_card`
+fn playing(hand: &str, top_card: &str) -> (Room, Connections) {
+    let (mut room, connections) = common::started(GameType::Carbo, 3);
+    room.carbo_mut().turn.set(0);
+    room.carbo_mut().top_card.set(cards(top_card));
+    let player = room.players[0].value_mut().as_mut().unwrap();
+    player.carbo_mut().visible_cards.set(cards(hand));
+    player.carbo_mut().num_cards.set(cards(hand).len() as u8);
+    (room, connections)
+}
+
+fn can_send(room: &Room, event: CarboClientEvent, player_index: usize) -> bool {
+    validate_client_event(room, &ClientEvent::CarboEvent(event), player_index)
+}
+
+fn can_play(room: &Room, card: &str) -> bool {
+    can_send(room, CarboClientEvent::PlayCard { card: cards(card) }, 0)
+}
+
+fn send(
+    room: &mut Room,
+    connections: &mut Connections,
+    event: CarboClientEvent,
+    player_index: usize,
+) {
+    common::send(
+        room,
+        connections,
+        ClientEvent::CarboEvent(event),
+        player_index,
+    );
+}
+
+fn play(room: &mut Room, connections: &mut Connections, card: &str) {
+    send(
+        room,
+        connections,
+        CarboClientEvent::PlayCard { card: cards(card) },
+        0,
+    );
+}
+
+#[test]
+fn hands_are_smaller_with_more_than_four_players() {
+    for (num_players, hand_size) in [(4, HAND_SIZE), (5, LARGE_TABLE_HAND_SIZE)] {
+        let (room, _) = common::started(GameType::Carbo, num_players);
+        for index in 0..num_players {
+            assert_eq!(hand(&room, index).len() as usize, hand_size);
+        }
+        assert_eq!(
+            room.carbo().draw_pile.len(),
+            54 - num_players * hand_size - 1
+        );
+    }
+}
+
+#[test]
+fn cards_must_match_the_rank_or_suit() {
+    let (room, _) = playing("5S 9H 9S", "5H");
+    assert!(can_play(&room, "5S"));
+    assert!(can_play(&room, "9H"));
+    assert!(!can_play(&room, "9S"));
+}
+
+#[test]
+fn only_the_current_player_plays_their_own_cards() {
+    let (room, _) = playing("5S", "5H");
+    assert!(!can_play(&room, "5D"));
+    assert!(!can_play(&room, "5S JK"));
+    assert!(!can_send(
+        &room,
+        CarboClientEvent::PlayCard { card: cards("5S") },
+        1
+    ));
+    assert!(!can_send(&room, CarboClientEvent::Draw, 1));
+}
+
+#[test]
+fn eights_and_jokers_are_wild() {
+    let (mut room, mut connections) = playing("8S JK 4C 9D", "3H");
+    assert!(can_play(&room, "8S"));
+    assert!(can_play(&room, "JK"));
+    assert!(!can_play(&room, "4C"));
+
+    // Anything can go on top of a wild card
+    play(&mut room, &mut connections, "8S");
+    assert_eq!(*room.carbo().turn.value(), 1);
+    room.carbo_mut().turn.set(0);
+    assert!(can_play(&room, "4C"));
+    assert!(can_play(&room, "9D"));
+}
+
+#[test]
+fn playing_a_card_moves_it_to_the_top_of_the_discard_pile() {
+    let (mut room, mut connections) = playing("5S 9H", "5H");
+    play(&mut room, &mut connections, "5S");
+
+    assert_eq!(hand(&room, 0), cards("9H"));
+    assert_eq!(*player(&room, 0).carbo().num_cards.value(), 1);
+    assert_eq!(*room.carbo().top_card.value(), cards("5S"));
+    assert_eq!(room.carbo().discard_pile & cards("5H"), cards("5H"));
+    assert_eq!(*room.carbo().turn.value(), 1);
+}
+
+#[test]
+fn drawing_takes_a_card_and_ends_the_turn() {
+    let (mut room, mut connections) = playing("9S", "5H");
+    let draw_pile = room.carbo().draw_pile.clone();
+    send(&mut room, &mut connections, CarboClientEvent::Draw, 0);
+
+    // The card is drawn from anywhere in the pile, it's shuffled by the server's rng
+    let drawn = hand(&room, 0) - cards("9S");
+    assert_eq!(drawn.len(), 1);
+    assert!(draw_pile.contains(&drawn));
+    assert!(!room.carbo().draw_pile.contains(&drawn));
+    assert_eq!(*player(&room, 0).carbo().num_cards.value(), 2);
+    assert_eq!(*room.carbo().turn.value(), 1);
+}
+
+#[test]
+fn the_discard_pile_is_reshuffled_when_the_draw_pile_runs_out() {
+    let (mut room, mut connections) = playing("9S", "5H");
+    room.carbo_mut().draw_pile.clear();
+    room.carbo_mut().discard_pile = cards("3D 4D");
+    send(&mut room, &mut connections, CarboClientEvent::Draw, 0);
+
+    // One of them was drawn straight away, the other is left to draw
+    let drawn = hand(&room, 0) - cards("9S");
+    assert_eq!(drawn.len(), 1);
+    assert_eq!(room.carbo().draw_pile, [cards("3D 4D") - drawn]);
+    assert!(room.carbo().discard_pile.is_empty());
+    assert_eq!(*room.carbo().top_card.value(), cards("5H"));
+}
+
+#[test]
+fn drawing_with_nothing_left_passes_the_turn() {
+    let (mut room, mut connections) = playing("9S", "5H");
+    room.carbo_mut().draw_pile.clear();
+    room.carbo_mut().discard_pile = CardSet::EMPTY;
+    send(&mut room, &mut connections, CarboClientEvent::Draw, 0);
+
+    assert_eq!(hand(&room, 0), cards("9S"));
+    assert_eq!(*player(&room, 0).carbo().num_cards.value(), 1);
+    assert_eq!(*room.carbo().turn.value(), 1);
+}
+
+#[test]
+fn playing_the_last_card_wins_and_returns_to_the_lobby() {
+    let (mut room, mut connections) = playing("5S", "5H");
+    play(&mut room, &mut connections, "5S");
+
+    assert_eq!(*room.carbo().winner.value(), Some(0));
+    assert_eq!(*room.carbo().state.value(), CarboRoomState::Lobby);
+    assert_eq!(*room.state.value(), RoomState::Lobby);
+    assert!(!can_send(&room, CarboClientEvent::Draw, 1));
+
+    // And the host can start another one
+    let start = ClientEvent::CommonEvent(CommonClientEvent::StartGame);
+    common::send(&mut room, &mut connections, start, 0);
+    assert_eq!(*room.carbo().winner.value(), None);
+    assert_eq!(hand(&room, 0).len() as usize, HAND_SIZE);
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::cards;
+use shared::{
+    cards::{CardSet, DIAMONDS, HEARTS, NUM_RANKS, NUM_SUITS, ParseCardError, SPADES},
+    traits::ToFromBytes,
+};
+
+#[test]
+fn notation_round_trips_the_full_deck() {
+    let deck = CardSet::FULL_DECK.to_string();
+    assert_eq!(deck.split(' ').count(), CardSet::FULL_DECK.len() as usize);
+    assert!(deck.starts_with("3D 3C 3H 3S 4D"));
+    assert!(deck.ends_with("2D 2C 2H 2S JK JK"));
+    assert_eq!(cards(&deck), CardSet::FULL_DECK);
+}
+
+#[test]
+fn notation_matches_the_bit_encoding() {
+    assert_eq!(
+        cards("3H 3S JK"),
+        CardSet::card(0, HEARTS)
+            | CardSet::card(0, SPADES)
+            | CardSet::JOKERS.iter().next().unwrap()
+    );
+    assert_eq!(
+        cards("2d td"),
+        CardSet::card(NUM_RANKS - 1, DIAMONDS) | CardSet::card(7, DIAMONDS)
+    );
+    assert_eq!(cards("JK JK"), CardSet::JOKERS);
+    assert_eq!(cards("  "), CardSet::EMPTY);
+    assert_eq!(CardSet::EMPTY.to_string(), "");
+}
+
+#[test]
+fn invalid_notation_is_rejected() {
+    assert_eq!(
+        "3H 1S".parse::<CardSet>(),
+        Err(ParseCardError::InvalidCard("1S".to_string()))
+    );
+    assert_eq!(
+        "10H".parse::<CardSet>(),
+        Err(ParseCardError::InvalidCard("10H".to_string()))
+    );
+    assert_eq!(
+        "3H 3h".parse::<CardSet>(),
+        Err(ParseCardError::DuplicateCard("3h".to_string()))
+    );
+    assert_eq!(
+        "JK JK JK".parse::<CardSet>(),
+        Err(ParseCardError::TooManyJokers)
+    );
+}
+
+#[test]
+fn debug_output_shows_the_notation() {
+    assert_eq!(format!("{:?}", cards("4C QS")), "CardSet(4C QS)");
+}
+
+#[test]
+fn sequences_fill_gaps_with_jokers() {
+    assert_eq!(cards("3H 4H 5H").sequence(), Some(0));
+    assert_eq!(cards("3H 5H 6H JK").sequence(), Some(0));
+    assert_eq!(cards("3H 6H JK JK").sequence(), Some(0));
+    assert_eq!(cards("3H 6H JK").sequence(), None);
+}
+
+#[test]
+fn sequences_extend_down_once_they_reach_the_top_rank() {
+    // The joker goes above the run while there's room, then below it once the run reaches the 2
+    assert_eq!(cards("QH KH JK").sequence(), Some(9));
+    assert_eq!(cards("AH 2H JK").sequence(), Some(10));
+    assert_eq!(cards("2H JK JK").sequence(), Some(10));
+}
+
+#[test]
+fn sequences_need_a_single_suit() {
+    assert_eq!(cards("3H 4S 5H").sequence(), None);
+    assert_eq!(cards("3H 4S JK").sequence(), None);
+    assert_eq!(cards("JK JK").sequence(), None);
+    assert_eq!(CardSet::EMPTY.sequence(), None);
+}
+
+#[test]
+fn by_rank_groups_the_standard_cards() {
+    assert_eq!(
+        cards("3H 3S 5D 2C JK").by_rank().collect::<Vec<_>>(),
+        [
+            (0, cards("3H 3S")),
+            (2, cards("5D")),
+            (NUM_RANKS - 1, cards("2C"))
+        ]
+    );
+    assert_eq!(cards("JK").by_rank().count(), 0);
+}
+
+#[test]
+fn contains_and_is_subset_are_each_others_inverse() {
+    let hand = cards("3H 5D KS JK");
+    assert!(hand.contains(cards("3H JK")));
+    assert!(hand.contains(CardSet::EMPTY));
+    assert!(!hand.contains(cards("3H 3S")));
+
+    assert!(cards("3H JK").is_subset(hand));
+    assert!(hand.is_subset(hand));
+    assert!(!hand.is_subset(cards("3H")));
+}
+
+#[test]
+fn all_of_suit_has_every_rank_and_no_jokers() {
+    let mut standard = CardSet::EMPTY;
+    for suit in 0..NUM_SUITS {
+        let all = CardSet::all_of_suit(suit);
+        assert_eq!(all.len(), NUM_RANKS as u32);
+        assert_eq!(all.suits().collect::<Vec<_>>(), [suit]);
+        assert_eq!(all.ranks().count(), NUM_RANKS as usize);
+        assert!(all.jokers().is_empty());
+        standard |= all;
+    }
+    assert_eq!(standard, CardSet::FULL_DECK.standard());
+}
+
+#[test]
+fn bits_past_the_jokers_are_not_cards() {
+    let past_jokers = u64::MAX - CardSet::FULL_DECK.bits();
+    assert_eq!(CardSet::from_bits(past_jokers), CardSet::EMPTY);
+    assert_eq!(
+        CardSet::from_bits(CardSet::JOKERS.bits() | past_jokers),
+        CardSet::JOKERS
+    );
+
+    // A set decoded off the wire can still have them, they shouldn't look like jokers
+    let decoded = CardSet::from_bytes(&(1u64 << 60).to_le_bytes());
+    assert_eq!(decoded.to_string(), "??");
+}
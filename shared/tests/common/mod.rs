@@ -0,0 +1,69 @@
+// Fixtures shared by the integration tests, each test file only uses some of them
+#![allow(dead_code)]
+
+use shared::{
+    cards::CardSet,
+    logic::{handle_client_event, validate_client_event},
+    traits::{GameSignal, NetworkingSend},
+    types::{ClientEvent, CommonClientEvent, GameType, MAX_PLAYERS, Player, Room, ServerEvent},
+};
+
+pub type Connections = [Option<Recorder>; MAX_PLAYERS];
+
+// Keeps every event the player was sent, in order
+#[derive(Default)]
+pub struct Recorder(pub Vec<ServerEvent>);
+
+impl NetworkingSend for Recorder {
+    fn send(&mut self, event: &ServerEvent) {
+        self.0.push(event.clone());
+    }
+}
+
+// A room in the lobby with the first `num_players` seats taken, player 0 is the host
+pub fn lobby(game: GameType, num_players: usize) -> Room {
+    let mut room = Room::default();
+    for player in room.players.iter_mut().take(num_players) {
+        player.set(Some(Player::default()));
+    }
+    room.set_game(game);
+    room
+}
+
+pub fn connected(num_players: usize) -> Connections {
+    let mut connections: Connections = Default::default();
+    for connection in connections.iter_mut().take(num_players) {
+        *connection = Some(Recorder::default());
+    }
+    connections
+}
+
+// Sends an event the way a client would, it has to be valid
+pub fn send(
+    room: &mut Room,
+    connections: &mut Connections,
+    event: ClientEvent,
+    player_index: usize,
+) {
+    assert!(
+        validate_client_event(room, &event, player_index),
+        "{:?}",
+        event
+    );
+    handle_client_event(room, &event, connections, player_index);
+}
+
+// Starts the game the way the host would, with everyone connected
+pub fn started(game: GameType, num_players: usize) -> (Room, Connections) {
+    let mut room = lobby(game, num_players);
+    let mut connections = connected(num_players);
+
+    let start = ClientEvent::CommonEvent(CommonClientEvent::StartGame);
+    send(&mut room, &mut connections, start, 0);
+    (room, connections)
+}
+
+// Cards in the shared notation, rank then suit (`3H`, `TD`, `2S`) with `JK` for a joker
+pub fn cards(notation: &str) -> CardSet {
+    notation.parse().expect("valid card notation")
+}
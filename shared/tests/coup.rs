@@ -0,0 +1,837 @@
+mod common;
+
+use common::Connections;
+use shared::{
+    games::coup::{
+        ASSASSINATE_COST, Allegiance, CARDS_PER_ROLE, CONVERT_OTHER_COST, CONVERT_SELF_COST,
+        COUP_COST, COURT_ROLES, CoupClientEvent, CoupServerEvent, CoupSettings, EXCHANGE_DRAW,
+        MUST_COUP_COINS, PlayerActionType, Role, STARTING_COINS,
+    },
+    logic::{self, handle_server_event, validate_client_event},
+    traits::GameSignal,
+    types::{ClientEvent, CommonClientEvent, GameType, Player, Room, RoomState, ServerEvent},
+};
+
+fn player(room: &Room, player_index: usize) -> &Player {
+    room.players[player_index].value().as_ref().unwrap()
+}
+
+fn roles(room: &Room, player_index: usize) -> Vec<Role> {
+    player(room, player_index)
+        .coup()
+        .cards
+        .iter()
+        .map(|card| *card.role.value())
+        .collect()
+}
+
+fn set_roles(room: &mut Room, player_index: usize, roles: [Role; 2]) {
+    let player = room.players[player_index].value_mut().as_mut().unwrap();
+    for (card, role) in player.coup_mut().cards.iter_mut().zip(roles) {
+        card.role.set(role);
+    }
+}
+
+fn set_coins(room: &mut Room, player_index: usize, coins: u8) {
+    let player = room.players[player_index].value_mut().as_mut().unwrap();
+    player.coup_mut().coins.set(coins);
+}
+
+// A game of 4 where it's player 0's turn
+fn started() -> (Room, Connections) {
+    let (mut room, connections) = common::started(GameType::Coup, 4);
+    room.coup_mut().turn.set(0);
+    (room, connections)
+}
+
+fn can_send(room: &Room, event: CoupClientEvent, player_index: usize) -> bool {
+    validate_client_event(room, &ClientEvent::CoupEvent(event), player_index)
+}
+
+fn can_act(room: &Room, action: PlayerActionType) -> bool {
+    can_send(room, CoupClientEvent::Action { action }, 0)
+}
+
+fn send(
+    room: &mut Room,
+    connections: &mut Connections,
+    event: CoupClientEvent,
+    player_index: usize,
+) {
+    common::send(
+        room,
+        connections,
+        ClientEvent::CoupEvent(event),
+        player_index,
+    );
+}
+
+fn act(room: &mut Room, connections: &mut Connections, action: PlayerActionType) {
+    send(room, connections, CoupClientEvent::Action { action }, 0);
+}
+
+#[test]
+fn coup_needs_three_to_six_players() {
+    let start = ClientEvent::CommonEvent(CommonClientEvent::StartGame);
+    for (num_players, valid) in [(2, false), (3, true), (6, true), (7, false)] {
+        let room = common::lobby(GameType::Coup, num_players);
+        assert_eq!(validate_client_event(&room, &start, 0), valid);
+    }
+}
+
+#[test]
+fn everyone_is_dealt_two_roles_and_two_coins() {
+    let (room, _) = common::started(GameType::Coup, 4);
+    assert_eq!(room.coup().deck.len(), 15 - 4 * 2);
+
+    let mut court = room.coup().deck.clone();
+    for index in 0..4 {
+        assert_eq!(*player(&room, index).coup().coins.value(), STARTING_COINS);
+        court.extend(roles(&room, index));
+    }
+    for role in COURT_ROLES {
+        let count = court.iter().filter(|card| **card == role).count();
+        assert_eq!(count, CARDS_PER_ROLE);
+    }
+}
+
+#[test]
+fn players_are_only_sent_their_own_roles() {
+    let (room, connections) = common::started(GameType::Coup, 4);
+    // After the commitment to the shuffle seed
+    let [_, event] = &connections[1].as_ref().unwrap().0[..] else {
+        panic!("expected a single GameStarted");
+    };
+    let ServerEvent::CoupEvent(CoupServerEvent::GameStarted { cards, .. }) = event else {
+        panic!("unexpected event {:?}", event);
+    };
+    assert_eq!(cards[..], roles(&room, 1)[..]);
+
+    let mut client = common::lobby(GameType::Coup, 4);
+    handle_server_event(&mut client, event, Some(1), false);
+    assert_eq!(roles(&client, 1), roles(&room, 1));
+    for other in [0, 2, 3] {
+        assert_eq!(roles(&client, other), [Role::Unknown; 2]);
+        assert_eq!(*player(&client, other).coup().coins.value(), STARTING_COINS);
+    }
+}
+
+#[test]
+fn actions_need_enough_coins() {
+    let (mut room, _) = started();
+    set_coins(&mut room, 0, COUP_COST - 1);
+    assert!(!can_act(&room, PlayerActionType::Coup { target: 1 }));
+    assert!(can_act(&room, PlayerActionType::Assassinate { target: 1 }));
+
+    set_coins(&mut room, 0, ASSASSINATE_COST - 1);
+    assert!(!can_act(&room, PlayerActionType::Assassinate { target: 1 }));
+    assert!(can_act(&room, PlayerActionType::Steal { target: 1 }));
+}
+
+#[test]
+fn ten_coins_forces_a_coup() {
+    let (mut room, _) = started();
+    set_coins(&mut room, 0, MUST_COUP_COINS);
+    assert!(!can_act(&room, PlayerActionType::Income));
+    assert!(!can_act(&room, PlayerActionType::Tax));
+    assert!(!can_act(&room, PlayerActionType::Assassinate { target: 1 }));
+    assert!(can_act(&room, PlayerActionType::Coup { target: 1 }));
+}
+
+#[test]
+fn targets_must_be_other_living_players() {
+    let (mut room, _) = started();
+    set_coins(&mut room, 0, COUP_COST);
+    assert!(!can_act(&room, PlayerActionType::Steal { target: 0 }));
+    assert!(!can_act(&room, PlayerActionType::Coup { target: 0 }));
+
+    let target = room.players[2].value_mut().as_mut().unwrap();
+    for card in target.coup_mut().cards.iter_mut() {
+        card.revealed.set(true);
+    }
+    assert!(!can_act(&room, PlayerActionType::Steal { target: 2 }));
+    assert!(!can_act(&room, PlayerActionType::Coup { target: 2 }));
+    assert!(can_act(&room, PlayerActionType::Coup { target: 3 }));
+}
+
+#[test]
+fn only_the_player_whose_turn_it_is_can_act() {
+    let (room, _) = started();
+    assert!(!can_send(
+        &room,
+        CoupClientEvent::Action {
+            action: PlayerActionType::Income
+        },
+        1
+    ));
+}
+
+#[test]
+fn income_resolves_straight_away() {
+    let (mut room, mut connections) = started();
+    act(&mut room, &mut connections, PlayerActionType::Income);
+    assert_eq!(*player(&room, 0).coup().coins.value(), STARTING_COINS + 1);
+    assert!(room.coup().last_action.value().is_none());
+    assert_eq!(*room.coup().turn.value(), 1);
+}
+
+#[test]
+fn costs_are_paid_up_front() {
+    let (mut room, mut connections) = started();
+    set_coins(&mut room, 0, COUP_COST + 1);
+    act(
+        &mut room,
+        &mut connections,
+        PlayerActionType::Coup { target: 2 },
+    );
+    assert_eq!(*player(&room, 0).coup().coins.value(), 1);
+
+    // The target has to lose a card before the turn moves on
+    assert_eq!(*room.coup().influence_loss.value(), [2]);
+    assert_eq!(*room.coup().turn.value(), 0);
+
+    let (mut room, mut connections) = started();
+    set_coins(&mut room, 0, ASSASSINATE_COST);
+    act(
+        &mut room,
+        &mut connections,
+        PlayerActionType::Assassinate { target: 2 },
+    );
+    assert_eq!(*player(&room, 0).coup().coins.value(), 0);
+    assert!(room.coup().last_action.value().is_some());
+}
+
+#[test]
+fn proving_a_challenge_replaces_the_card_and_punishes_the_challenger() {
+    let (mut room, mut connections) = started();
+    set_roles(&mut room, 0, [Role::Duke, Role::Contessa]);
+    act(&mut room, &mut connections, PlayerActionType::Tax);
+    send(&mut room, &mut connections, CoupClientEvent::Challenge, 1);
+
+    // Only the challenged player can resolve it
+    assert!(!can_send(
+        &room,
+        CoupClientEvent::ResolveChallenge { card: 0 },
+        1
+    ));
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ResolveChallenge { card: 0 },
+        0,
+    );
+
+    let card = &player(&room, 0).coup().cards[0];
+    assert!(!card.revealed.value());
+    assert_ne!(*card.role.value(), Role::Unknown);
+    assert_eq!(room.coup().deck.len(), 15 - 4 * 2);
+    assert_eq!(*player(&room, 0).coup().coins.value(), STARTING_COINS + 3);
+    assert_eq!(*room.coup().influence_loss.value(), [1]);
+}
+
+#[test]
+fn a_failed_claim_loses_the_card_and_cancels_the_action() {
+    let (mut room, mut connections) = started();
+    set_roles(&mut room, 0, [Role::Contessa, Role::Captain]);
+    set_coins(&mut room, 0, ASSASSINATE_COST);
+    act(
+        &mut room,
+        &mut connections,
+        PlayerActionType::Assassinate { target: 2 },
+    );
+    send(&mut room, &mut connections, CoupClientEvent::Challenge, 2);
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ResolveChallenge { card: 1 },
+        0,
+    );
+
+    let card = &player(&room, 0).coup().cards[1];
+    assert!(card.revealed.value());
+    assert_eq!(*card.role.value(), Role::Captain);
+
+    // What was paid for the action is given back
+    assert_eq!(*player(&room, 0).coup().coins.value(), ASSASSINATE_COST);
+    assert!(room.coup().last_action.value().is_none());
+    assert!(room.coup().influence_loss.value().is_empty());
+    assert_eq!(*room.coup().turn.value(), 1);
+}
+
+#[test]
+fn replacement_cards_reach_the_server_room_without_a_connection() {
+    let (mut room, mut connections) = started();
+    set_roles(&mut room, 0, [Role::Duke, Role::Contessa]);
+    act(&mut room, &mut connections, PlayerActionType::Tax);
+    send(&mut room, &mut connections, CoupClientEvent::Challenge, 1);
+
+    // The challenged player leaves before the server gets to send them their new card
+    connections[0] = None;
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ResolveChallenge { card: 0 },
+        0,
+    );
+    assert_ne!(
+        *player(&room, 0).coup().cards[0].role.value(),
+        Role::Unknown
+    );
+}
+
+// Player 0 is the ambassador, and proving a challenge lets the exchange go ahead
+fn exchanging() -> (Room, Connections) {
+    let (mut room, mut connections) = started();
+    set_roles(&mut room, 0, [Role::Ambassador, Role::Contessa]);
+    act(&mut room, &mut connections, PlayerActionType::Exchange);
+    send(&mut room, &mut connections, CoupClientEvent::Challenge, 1);
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ResolveChallenge { card: 0 },
+        0,
+    );
+    (room, connections)
+}
+
+#[test]
+fn the_ambassador_is_privately_sent_the_drawn_cards() {
+    let (room, connections) = exchanging();
+    assert_eq!(*room.coup().exchange.value(), Some(0));
+    assert_eq!(room.coup().exchange_cards.len(), EXCHANGE_DRAW);
+    assert_eq!(room.coup().deck.len(), 15 - 4 * 2 - EXCHANGE_DRAW);
+
+    let drawn = |player_index: usize| {
+        connections[player_index]
+            .as_ref()
+            .unwrap()
+            .0
+            .iter()
+            .any(|event| {
+                matches!(
+                    event,
+                    ServerEvent::CoupEvent(CoupServerEvent::ExchangeDrawn { .. })
+                )
+            })
+    };
+    assert!(drawn(0));
+    assert!(!drawn(1));
+}
+
+#[test]
+fn the_ambassador_keeps_as_many_cards_as_they_have_influence() {
+    let (mut room, mut connections) = exchanging();
+    let drawn = room.coup().exchange_cards.clone();
+
+    assert!(!can_send(
+        &room,
+        CoupClientEvent::ChooseExchange { keep: 0b0100 },
+        0
+    ));
+    assert!(!can_send(
+        &room,
+        CoupClientEvent::ChooseExchange { keep: 0b0111 },
+        0
+    ));
+    assert!(!can_send(
+        &room,
+        CoupClientEvent::ChooseExchange { keep: 0b1_0001 },
+        0
+    ));
+    assert!(!can_send(
+        &room,
+        CoupClientEvent::ChooseExchange { keep: 0b1100 },
+        1
+    ));
+
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ChooseExchange { keep: 0b1100 },
+        0,
+    );
+    assert_eq!(roles(&room, 0), drawn);
+    assert_eq!(room.coup().deck.len(), 15 - 4 * 2);
+    assert!(room.coup().exchange.value().is_none());
+    assert!(room.coup().exchange_cards.is_empty());
+}
+
+fn allow(room: &mut Room, connections: &mut Connections, player_index: usize) {
+    send(room, connections, CoupClientEvent::Allow, player_index);
+}
+
+#[test]
+fn actions_resolve_once_everyone_allows() {
+    let (mut room, mut connections) = started();
+    act(&mut room, &mut connections, PlayerActionType::Tax);
+    allow(&mut room, &mut connections, 1);
+    allow(&mut room, &mut connections, 2);
+    assert!(!can_send(&room, CoupClientEvent::Allow, 1));
+    assert_eq!(*player(&room, 0).coup().coins.value(), STARTING_COINS);
+
+    allow(&mut room, &mut connections, 3);
+    assert_eq!(*player(&room, 0).coup().coins.value(), STARTING_COINS + 3);
+    assert!(room.coup().last_action.value().is_none());
+    assert_eq!(*room.coup().turn.value(), 1);
+}
+
+#[test]
+fn only_players_who_could_stop_the_action_are_waited_on() {
+    let (mut room, mut connections) = started();
+    act(
+        &mut room,
+        &mut connections,
+        PlayerActionType::Steal { target: 2 },
+    );
+    assert!(!can_send(&room, CoupClientEvent::Allow, 0));
+    assert!(!can_send(&room, CoupClientEvent::Allow, 1));
+    assert!(!can_send(&room, CoupClientEvent::Challenge, 3));
+
+    allow(&mut room, &mut connections, 2);
+    assert_eq!(*player(&room, 0).coup().coins.value(), STARTING_COINS + 2);
+    assert_eq!(*player(&room, 2).coup().coins.value(), 0);
+}
+
+#[test]
+fn timeouts_allow_for_anyone_who_has_not_responded() {
+    let (mut room, mut connections) = started();
+    room.coup_mut().settings.set(CoupSettings {
+        response_timeout: Some(5),
+        ..Default::default()
+    });
+    assert_eq!(logic::response_deadline(&room), None);
+
+    act(&mut room, &mut connections, PlayerActionType::ForeignAid);
+    allow(&mut room, &mut connections, 1);
+    let Some((window, 5)) = logic::response_deadline(&room) else {
+        panic!("expected a deadline");
+    };
+
+    // A timeout for an earlier window does nothing
+    logic::handle_response_timeout(&mut room, &mut connections, window.wrapping_sub(1));
+    assert!(room.coup().last_action.value().is_some());
+
+    logic::handle_response_timeout(&mut room, &mut connections, window);
+    assert_eq!(*player(&room, 0).coup().coins.value(), STARTING_COINS + 2);
+    assert_eq!(logic::response_deadline(&room), None);
+}
+
+#[test]
+fn only_the_host_changes_settings_in_the_lobby() {
+    let settings = CoupClientEvent::SetSettings {
+        settings: CoupSettings {
+            response_timeout: Some(10),
+            ..Default::default()
+        },
+    };
+
+    let room = common::lobby(GameType::Coup, 3);
+    assert!(can_send(&room, settings, 0));
+    assert!(!can_send(&room, settings, 1));
+
+    let (room, _) = started();
+    assert!(!can_send(&room, settings, 0));
+}
+
+fn block(room: &mut Room, connections: &mut Connections, claim: Role, player_index: usize) {
+    send(
+        room,
+        connections,
+        CoupClientEvent::Counteraction { claim },
+        player_index,
+    );
+}
+
+// Player 0 tries to assassinate player 2
+fn assassinating() -> (Room, Connections) {
+    let (mut room, mut connections) = started();
+    set_roles(&mut room, 0, [Role::Assassin, Role::Duke]);
+    set_coins(&mut room, 0, ASSASSINATE_COST);
+    act(
+        &mut room,
+        &mut connections,
+        PlayerActionType::Assassinate { target: 2 },
+    );
+    (room, connections)
+}
+
+#[test]
+fn an_unchallenged_block_stops_the_action() {
+    let (mut room, mut connections) = started();
+    act(&mut room, &mut connections, PlayerActionType::ForeignAid);
+    block(&mut room, &mut connections, Role::Duke, 1);
+    assert!(!can_send(
+        &room,
+        CoupClientEvent::Counteraction { claim: Role::Duke },
+        2
+    ));
+
+    for player_index in [0, 2, 3] {
+        allow(&mut room, &mut connections, player_index);
+    }
+    assert_eq!(*player(&room, 0).coup().coins.value(), STARTING_COINS);
+    assert!(room.coup().last_action.value().is_none());
+    assert!(room.coup().last_counteraction.value().is_none());
+    assert_eq!(*room.coup().turn.value(), 1);
+}
+
+#[test]
+fn a_block_that_survives_a_challenge_still_costs_the_assassin() {
+    let (mut room, mut connections) = assassinating();
+    set_roles(&mut room, 2, [Role::Duke, Role::Contessa]);
+    block(&mut room, &mut connections, Role::Contessa, 2);
+    send(&mut room, &mut connections, CoupClientEvent::Challenge, 0);
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ResolveChallenge { card: 1 },
+        2,
+    );
+
+    assert!(!player(&room, 2).coup().cards[1].revealed.value());
+    assert_eq!(*room.coup().influence_loss.value(), [0]);
+    assert_eq!(*player(&room, 0).coup().coins.value(), 0);
+    assert!(room.coup().last_action.value().is_none());
+}
+
+#[test]
+fn a_bluffed_block_lets_the_action_through() {
+    let (mut room, mut connections) = assassinating();
+    set_roles(&mut room, 2, [Role::Duke, Role::Captain]);
+    block(&mut room, &mut connections, Role::Contessa, 2);
+    send(&mut room, &mut connections, CoupClientEvent::Challenge, 0);
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ResolveChallenge { card: 0 },
+        2,
+    );
+
+    // One card for the bluff, and the assassination still has to take another
+    assert!(player(&room, 2).coup().cards[0].revealed.value());
+    assert_eq!(*room.coup().influence_loss.value(), [2]);
+    assert!(room.coup().last_action.value().is_none());
+}
+
+#[test]
+fn losing_a_challenge_still_lets_the_target_block() {
+    let (mut room, mut connections) = assassinating();
+    send(&mut room, &mut connections, CoupClientEvent::Challenge, 2);
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ResolveChallenge { card: 0 },
+        0,
+    );
+    assert_eq!(*room.coup().influence_loss.value(), [2]);
+
+    // The assassination is still waiting on the target, who can claim the contessa
+    assert!(!can_send(&room, CoupClientEvent::Challenge, 2));
+    block(&mut room, &mut connections, Role::Contessa, 2);
+    assert!(room.coup().last_counteraction.value().is_some());
+}
+
+fn reveal(room: &mut Room, connections: &mut Connections, card: u8, player_index: usize) {
+    send(
+        room,
+        connections,
+        CoupClientEvent::RevealCard { card },
+        player_index,
+    );
+}
+
+#[test]
+fn the_target_chooses_which_card_to_lose() {
+    let (mut room, mut connections) = started();
+    set_coins(&mut room, 0, COUP_COST);
+    act(
+        &mut room,
+        &mut connections,
+        PlayerActionType::Coup { target: 2 },
+    );
+    assert_eq!(*room.coup().influence_loss.value(), [2]);
+    assert!(!can_send(&room, CoupClientEvent::RevealCard { card: 0 }, 0));
+
+    reveal(&mut room, &mut connections, 1, 2);
+    assert!(!player(&room, 2).coup().cards[0].revealed.value());
+    assert!(player(&room, 2).coup().cards[1].revealed.value());
+    assert!(room.coup().influence_loss.value().is_empty());
+    assert_eq!(*room.coup().turn.value(), 1);
+}
+
+#[test]
+fn eliminated_players_are_skipped() {
+    let (mut room, mut connections) = started();
+    let target = room.players[1].value_mut().as_mut().unwrap();
+    target.coup_mut().cards[0].revealed.set(true);
+    set_coins(&mut room, 0, COUP_COST);
+    act(
+        &mut room,
+        &mut connections,
+        PlayerActionType::Coup { target: 1 },
+    );
+
+    // Revealed cards can't be lost twice
+    assert!(!can_send(&room, CoupClientEvent::RevealCard { card: 0 }, 1));
+    reveal(&mut room, &mut connections, 1, 1);
+    assert_eq!(*room.coup().turn.value(), 2);
+}
+
+#[test]
+fn the_last_player_standing_wins_and_returns_to_the_lobby() {
+    let (mut room, mut connections) = started();
+    for player_index in 1..4 {
+        let player = room.players[player_index].value_mut().as_mut().unwrap();
+        player.coup_mut().cards[0].revealed.set(true);
+    }
+    for target in 1..4 {
+        set_coins(&mut room, 0, COUP_COST);
+        room.coup_mut().turn.set(0);
+        act(
+            &mut room,
+            &mut connections,
+            PlayerActionType::Coup { target },
+        );
+        reveal(&mut room, &mut connections, 1, target as usize);
+    }
+    assert_eq!(*room.coup().winner.value(), Some(0));
+    assert_eq!(*room.state.value(), RoomState::Lobby);
+}
+
+// A game of 4 with Reformation, where the factions alternate around the table from player 0's loyalists
+fn reformation() -> (Room, Connections) {
+    let mut room = common::lobby(GameType::Coup, 4);
+    let mut connections = common::connected(4);
+    let settings = CoupSettings {
+        reformation: true,
+        ..Default::default()
+    };
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::SetSettings { settings },
+        0,
+    );
+    let start = ClientEvent::CommonEvent(CommonClientEvent::StartGame);
+    common::send(&mut room, &mut connections, start, 0);
+    room.coup_mut().turn.set(0);
+    (room, connections)
+}
+
+fn allegiance(room: &Room, player_index: usize) -> Allegiance {
+    *player(room, player_index).coup().allegiance.value()
+}
+
+#[test]
+fn reformation_needs_the_setting() {
+    let (mut room, _) = started();
+    set_coins(&mut room, 0, CONVERT_OTHER_COST);
+    assert!(!can_act(&room, PlayerActionType::Convert { target: 1 }));
+    assert!(!can_act(&room, PlayerActionType::Embezzle));
+}
+
+#[test]
+fn reformation_only_lets_players_act_against_the_other_faction() {
+    let (mut room, _) = reformation();
+    let factions: Vec<_> = (0..4).map(|index| allegiance(&room, index)).collect();
+    assert_eq!(
+        factions,
+        [
+            Allegiance::Loyalist,
+            Allegiance::Reformist,
+            Allegiance::Loyalist,
+            Allegiance::Reformist
+        ]
+    );
+
+    set_coins(&mut room, 0, COUP_COST);
+    assert!(can_act(&room, PlayerActionType::Coup { target: 1 }));
+    assert!(!can_act(&room, PlayerActionType::Coup { target: 2 }));
+    assert!(!can_act(&room, PlayerActionType::Steal { target: 2 }));
+
+    // Once everyone left is on the same side, anyone is fair game
+    for index in [1, 3] {
+        let player = room.players[index].value_mut().as_mut().unwrap();
+        player.coup_mut().allegiance.set(Allegiance::Loyalist);
+    }
+    assert!(can_act(&room, PlayerActionType::Coup { target: 2 }));
+}
+
+#[test]
+fn reformation_only_lets_the_other_faction_block_foreign_aid() {
+    let (mut room, mut connections) = reformation();
+    act(&mut room, &mut connections, PlayerActionType::ForeignAid);
+    let claim = CoupClientEvent::Counteraction { claim: Role::Duke };
+    assert!(!can_send(&room, claim, 2));
+    assert!(can_send(&room, claim, 1));
+}
+
+#[test]
+fn conversions_fill_the_treasury_for_embezzling() {
+    let (mut room, mut connections) = reformation();
+    set_coins(&mut room, 0, CONVERT_SELF_COST);
+    assert!(!can_act(&room, PlayerActionType::Convert { target: 2 }));
+
+    act(
+        &mut room,
+        &mut connections,
+        PlayerActionType::Convert { target: 0 },
+    );
+    assert_eq!(allegiance(&room, 0), Allegiance::Reformist);
+    assert_eq!(*room.coup().treasury.value(), CONVERT_SELF_COST);
+    assert_eq!(*room.coup().turn.value(), 1);
+
+    room.coup_mut().turn.set(0);
+    set_coins(&mut room, 0, CONVERT_OTHER_COST);
+    act(
+        &mut room,
+        &mut connections,
+        PlayerActionType::Convert { target: 1 },
+    );
+    assert_eq!(allegiance(&room, 1), Allegiance::Loyalist);
+    assert_eq!(
+        *room.coup().treasury.value(),
+        CONVERT_SELF_COST + CONVERT_OTHER_COST
+    );
+    assert_eq!(*player(&room, 0).coup().coins.value(), 0);
+
+    room.coup_mut().turn.set(0);
+    act(&mut room, &mut connections, PlayerActionType::Embezzle);
+    for player_index in 1..4 {
+        allow(&mut room, &mut connections, player_index);
+    }
+    assert_eq!(
+        *player(&room, 0).coup().coins.value(),
+        CONVERT_SELF_COST + CONVERT_OTHER_COST
+    );
+    assert_eq!(*room.coup().treasury.value(), 0);
+}
+
+// A game of 4 with the inquisitor, where player 0 examines player 1's first card
+fn examining() -> (Room, Connections) {
+    let mut room = common::lobby(GameType::Coup, 4);
+    let mut connections = common::connected(4);
+    let settings = CoupSettings {
+        inquisitor: true,
+        ..Default::default()
+    };
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::SetSettings { settings },
+        0,
+    );
+    let start = ClientEvent::CommonEvent(CommonClientEvent::StartGame);
+    common::send(&mut room, &mut connections, start, 0);
+    room.coup_mut().turn.set(0);
+    set_roles(&mut room, 0, [Role::Inquisitor, Role::Duke]);
+    set_roles(&mut room, 1, [Role::Captain, Role::Contessa]);
+
+    act(
+        &mut room,
+        &mut connections,
+        PlayerActionType::Examine { target: 1 },
+    );
+    allow(&mut room, &mut connections, 1);
+    assert_eq!(*room.coup().examine.value(), Some((0, 1)));
+
+    // Only the target shows a card, and only the inquisitor decides what happens to it
+    assert!(!can_send(&room, CoupClientEvent::ShowCard { card: 0 }, 0));
+    assert!(!can_send(
+        &room,
+        CoupClientEvent::ForceSwap { swap: true },
+        0
+    ));
+    for connection in connections.iter_mut().flatten() {
+        connection.0.clear();
+    }
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ShowCard { card: 0 },
+        1,
+    );
+    (room, connections)
+}
+
+fn coup_events(connections: &Connections, player_index: usize) -> Vec<CoupServerEvent> {
+    connections[player_index]
+        .as_ref()
+        .unwrap()
+        .0
+        .iter()
+        .filter_map(|event| match event {
+            ServerEvent::CoupEvent(event) => Some(event.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn only_the_inquisitor_is_sent_the_examined_role() {
+    let (room, connections) = examining();
+    assert_eq!(*room.coup().examined_card.value(), Some(0));
+
+    let examined = |event: &CoupServerEvent| {
+        matches!(
+            event,
+            CoupServerEvent::CardExamined {
+                card: 0,
+                role: Role::Captain
+            }
+        )
+    };
+    assert!(coup_events(&connections, 0).iter().any(examined));
+    for player_index in 1..4 {
+        let events = coup_events(&connections, player_index);
+        assert!(matches!(
+            events[..],
+            [CoupServerEvent::CardShown { card: 0 }]
+        ));
+    }
+}
+
+#[test]
+fn the_inquisitor_can_let_the_target_keep_their_card() {
+    let (mut room, mut connections) = examining();
+    assert!(!can_send(
+        &room,
+        CoupClientEvent::ForceSwap { swap: false },
+        1
+    ));
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ForceSwap { swap: false },
+        0,
+    );
+    assert_eq!(roles(&room, 1), [Role::Captain, Role::Contessa]);
+    assert!(room.coup().examine.value().is_none());
+    assert_eq!(*room.coup().turn.value(), 1);
+}
+
+#[test]
+fn forcing_a_swap_privately_replaces_the_targets_card() {
+    let (mut room, mut connections) = examining();
+    let deck = room.coup().deck.len();
+    for connection in connections.iter_mut().flatten() {
+        connection.0.clear();
+    }
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ForceSwap { swap: true },
+        0,
+    );
+
+    assert_eq!(room.coup().deck.len(), deck);
+    assert!(room.coup().examine.value().is_none());
+    assert_eq!(*room.coup().turn.value(), 1);
+
+    let replaced =
+        |event: &CoupServerEvent| matches!(event, CoupServerEvent::CardReplaced { card: 0, .. });
+    assert!(coup_events(&connections, 1).iter().any(replaced));
+    for player_index in [0, 2, 3] {
+        assert!(!coup_events(&connections, player_index).iter().any(replaced));
+    }
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use shared::{
+    games::carbo::CarboServerEvent,
+    logic::{handle_server_event, start_game},
+    traits::{GameSignal, Networking, ToFromBytes},
+    types::{CommonServerEvent, GameType, Room, RoomState, ServerEvent},
+};
+
+const CLIENT: usize = 1;
+
+// Starts a game on a server and returns the events the client was sent along with the seed and nonce to reveal
+fn start(game: GameType, seed: u64) -> (Vec<ServerEvent>, ServerEvent) {
+    let mut room = common::lobby(game, 4);
+    let mut connections = common::connected(4);
+
+    start_game(&mut room, &mut connections, seed);
+    let reveal = ServerEvent::CommonEvent(CommonServerEvent::SeedRevealed {
+        seed: room.rng.seed(),
+        nonce: room.rng.nonce(),
+    });
+    let sent = connections[CLIENT].take().unwrap().0;
+    (sent, reveal)
+}
+
+fn client_verifies(game: GameType, events: &[ServerEvent], reveal: &ServerEvent) -> Option<bool> {
+    let mut room = Room::from_bytes(&common::lobby(game, 4).to_bytes());
+    room.player_index.set(CLIENT as u8);
+    for event in events.iter().chain([reveal]) {
+        handle_server_event(&mut room, event, Some(CLIENT), false);
+    }
+    *room.deal_verified.value()
+}
+
+#[test]
+fn an_honest_deal_is_verified() {
+    for game in [GameType::Tycoon, GameType::Carbo, GameType::Coup] {
+        let (events, reveal) = start(game, 7);
+        assert!(matches!(
+            events[0],
+            ServerEvent::CommonEvent(CommonServerEvent::SeedCommitted { .. })
+        ));
+        assert_eq!(
+            client_verifies(game, &events, &reveal),
+            Some(true),
+            "{:?}",
+            game
+        );
+    }
+}
+
+#[test]
+fn a_rigged_deal_is_caught() {
+    for game in [GameType::Tycoon, GameType::Carbo, GameType::Coup] {
+        let (events, reveal) = start(game, 7);
+        let (rigged, _) = start(game, 8);
+
+        // Commit to one seed but deal from another
+        let mut tampered = vec![events[0].clone()];
+        tampered.extend(rigged.into_iter().skip(1));
+        assert_eq!(
+            client_verifies(game, &tampered, &reveal),
+            Some(false),
+            "{:?}",
+            game
+        );
+
+        // Reveal a different seed than the one committed to
+        let ServerEvent::CommonEvent(CommonServerEvent::SeedRevealed { nonce, .. }) = reveal else {
+            unreachable!();
+        };
+        let wrong_seed =
+            ServerEvent::CommonEvent(CommonServerEvent::SeedRevealed { seed: 8, nonce });
+        assert_eq!(
+            client_verifies(game, &events, &wrong_seed),
+            Some(false),
+            "{:?}",
+            game
+        );
+    }
+}
+
+#[test]
+fn nothing_is_verified_without_a_commitment() {
+    let (events, reveal) = start(GameType::Tycoon, 7);
+    assert_eq!(
+        client_verifies(GameType::Tycoon, &events[1..], &reveal),
+        None
+    );
+}
+
+#[test]
+fn only_the_opening_deal_is_checked() {
+    for game in [GameType::Tycoon, GameType::Carbo, GameType::Coup] {
+        let (mut events, reveal) = start(game, 7);
+        let (rigged, _) = start(game, 8);
+
+        // A later round dealt from another seed isn't caught, the verdict is only about the opening hand
+        events.extend(rigged.into_iter().skip(1));
+        assert_eq!(
+            client_verifies(game, &events, &reveal),
+            Some(true),
+            "{:?}",
+            game
+        );
+    }
+}
+
+#[test]
+fn the_seed_is_revealed_however_the_game_ends() {
+    let mut room = common::lobby(GameType::Carbo, 4);
+    let mut connections = common::connected(4);
+    start_game(&mut room, &mut connections, 7);
+
+    // Ended by the server itself rather than a client event, like a timeout or a disconnect would
+    let turn = *room.carbo().turn.value() as usize;
+    let player = room.players[turn].value_mut().as_mut().unwrap().carbo_mut();
+    player.num_cards.set(1);
+    let card = player.visible_cards.value().iter().next().unwrap();
+    connections.send_to_all(
+        &mut room,
+        ServerEvent::CarboEvent(CarboServerEvent::CardPlayed { card }),
+    );
+    assert_eq!(*room.state.value(), RoomState::Lobby);
+
+    let sent = &connections[CLIENT].as_ref().unwrap().0;
+    let reveals: Vec<_> = sent
+        .iter()
+        .filter_map(|event| match event {
+            ServerEvent::CommonEvent(CommonServerEvent::SeedRevealed { seed, .. }) => Some(*seed),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(reveals, [7]);
+    assert!(matches!(
+        sent.last(),
+        Some(ServerEvent::CommonEvent(
+            CommonServerEvent::SeedRevealed { .. }
+        ))
+    ));
+}
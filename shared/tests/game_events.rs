@@ -0,0 +1,247 @@
+mod common;
+
+use common::Connections;
+use shared::{
+    games::{
+        carbo::{CarboClientEvent, CarboRoom, CarboServerEvent},
+        coup::{
+            self, ASSASSINATE_COST, CARDS_PER_ROLE, COURT_ROLES, CoupClientEvent, PlayerActionType,
+            Role, STARTING_COINS, STEAL_AMOUNT,
+        },
+    },
+    logic,
+    outbox::{Audience, Outbox},
+    shuffle::GameRng,
+    traits::{GameLogic, GameSignal, Networking, ToFromBytes},
+    types::{ClientEvent, CommonServerEvent, GameType, Room, ServerEvent},
+};
+
+fn send(
+    room: &mut Room,
+    connections: &mut Connections,
+    event: CoupClientEvent,
+    player_index: usize,
+) {
+    common::send(
+        room,
+        connections,
+        ClientEvent::CoupEvent(event),
+        player_index,
+    );
+}
+
+#[test]
+fn drawing_returns_the_events_without_touching_the_room() {
+    let (mut room, mut connections) = common::started(GameType::Carbo, 4);
+    let turn = *room.carbo().turn.value() as usize;
+    let draw_pile = room.carbo().draw_pile.len();
+
+    let events = CarboRoom::handle_client_game_event(
+        &room,
+        &CarboClientEvent::Draw,
+        turn,
+        &mut GameRng::new(3, [0; 32]),
+    );
+    assert_eq!(room.carbo().draw_pile.len(), draw_pile);
+
+    let [
+        (
+            Audience::All,
+            ServerEvent::CarboEvent(CarboServerEvent::CardDrawn {
+                drew: true,
+                reshuffled: false,
+            }),
+        ),
+        (audience, ServerEvent::CarboEvent(CarboServerEvent::CardReceived { card })),
+    ] = &events[..]
+    else {
+        panic!("unexpected events {:?}", events);
+    };
+    assert_eq!(*audience, Audience::Player(turn));
+    let card = *card;
+
+    connections.deliver(&mut room, events);
+    assert_eq!(room.carbo().draw_pile.len(), draw_pile - 1);
+    assert!(!room.carbo().draw_pile.contains(&card));
+    let hand = *room.players[turn]
+        .value()
+        .as_ref()
+        .unwrap()
+        .carbo()
+        .visible_cards
+        .value();
+    assert!(hand.contains(card));
+}
+
+#[test]
+fn exchanged_cards_go_back_into_the_deck() {
+    let (mut room, mut connections) = common::started(GameType::Coup, 4);
+    let turn = *room.coup().turn.value() as usize;
+    let deck = room.coup().deck.len();
+
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::Action {
+            action: PlayerActionType::Exchange,
+        },
+        turn,
+    );
+    let window = room.coup().response_window;
+    logic::handle_response_timeout(&mut room, &mut connections, window);
+    assert_eq!(room.coup().exchange_cards.len(), coup::EXCHANGE_DRAW);
+    assert_eq!(room.coup().deck.len(), deck - coup::EXCHANGE_DRAW);
+
+    // Keep both drawn cards, so both of the player's cards go back
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ChooseExchange { keep: 0b1100 },
+        turn,
+    );
+    assert_eq!(room.coup().deck.len(), deck);
+
+    for role in COURT_ROLES {
+        let held = room
+            .players
+            .iter()
+            .filter_map(|player| player.value().as_ref())
+            .flat_map(|player| player.coup().cards.iter())
+            .filter(|card| *card.role.value() == role)
+            .count();
+        let in_deck = room
+            .coup()
+            .deck
+            .iter()
+            .filter(|card| **card == role)
+            .count();
+        assert_eq!(held + in_deck, CARDS_PER_ROLE, "{:?}", role);
+    }
+}
+
+// A game of 3 where player 2 is down to their last card and player 0 claims `role` to act against them
+fn challenged_on_last_card(role: Role, action: PlayerActionType) -> (Room, Connections) {
+    let (mut room, mut connections) = common::started(GameType::Coup, 3);
+    room.coup_mut().turn.set(0);
+    let player = room.players[0].value_mut().as_mut().unwrap();
+    player.coup_mut().cards[0].role.set(role);
+    player.coup_mut().coins.set(ASSASSINATE_COST);
+    let target = room.players[2].value_mut().as_mut().unwrap();
+    target.coup_mut().cards[1].revealed.set(true);
+
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::Action { action },
+        0,
+    );
+    send(&mut room, &mut connections, CoupClientEvent::Challenge, 2);
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::ResolveChallenge { card: 0 },
+        0,
+    );
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::RevealCard { card: 0 },
+        2,
+    );
+    (room, connections)
+}
+
+#[test]
+fn an_assassination_resolves_once_the_target_is_knocked_out() {
+    let (room, _) =
+        challenged_on_last_card(Role::Assassin, PlayerActionType::Assassinate { target: 2 });
+    assert!(room.coup().last_action.value().is_none());
+    assert!(room.coup().influence_loss.value().is_empty());
+    assert_eq!(*room.coup().turn.value(), 1);
+}
+
+#[test]
+fn a_steal_resolves_once_the_target_is_knocked_out() {
+    let (room, _) = challenged_on_last_card(Role::Captain, PlayerActionType::Steal { target: 2 });
+    assert!(room.coup().last_action.value().is_none());
+    let player = room.players[0].value().as_ref().unwrap();
+    assert_eq!(
+        *player.coup().coins.value(),
+        ASSASSINATE_COST + STARTING_COINS.min(STEAL_AMOUNT)
+    );
+    assert_eq!(*room.coup().turn.value(), 1);
+}
+
+#[test]
+fn outbox_previews_match_delivery_without_a_connection() {
+    let (mut room, mut connections) = common::started(GameType::Carbo, 3);
+    let turn = *room.carbo().turn.value() as usize;
+    let card = room.carbo().draw_pile[0];
+
+    let mut outbox = Outbox::new(&room);
+    outbox.send_to_all::<CarboRoom>(CarboServerEvent::CardDrawn {
+        drew: true,
+        reshuffled: false,
+    });
+    outbox.send_to::<CarboRoom>(CarboServerEvent::CardReceived { card }, turn);
+    let preview = outbox.room().to_bytes();
+
+    connections[turn] = None;
+    connections.deliver(&mut room, outbox.into_events());
+    assert_eq!(room.to_bytes(), preview);
+    assert!(!room.carbo().draw_pile.contains(&card));
+}
+
+#[test]
+fn reset_and_game_changes_swap_in_fresh_state() {
+    let (mut room, _) = common::started(GameType::Carbo, 5);
+    assert!(!room.carbo().draw_pile.is_empty());
+
+    let event = ServerEvent::CommonEvent(CommonServerEvent::ResetGame);
+    logic::handle_server_event(&mut room, &event, None, true);
+
+    assert!(room.carbo().draw_pile.is_empty());
+    let player = room.players[0].value().as_ref().unwrap();
+    assert_eq!(*player.carbo().num_cards.value(), 0);
+
+    // Only the new game's state is left, for the room and every player
+    let event = ServerEvent::CommonEvent(CommonServerEvent::GameChanged {
+        game: GameType::Coup,
+    });
+    logic::handle_server_event(&mut room, &event, None, true);
+
+    assert!(room.game_room.coup().is_some());
+    assert!(room.game_room.carbo().is_none());
+    for player in room
+        .players
+        .iter()
+        .filter_map(|player| player.value().as_ref())
+    {
+        assert!(player.game_player.coup().is_some());
+    }
+}
+
+#[test]
+fn response_timeouts_ignore_a_room_that_switched_games() {
+    let (mut room, mut connections) = common::started(GameType::Coup, 3);
+    let turn = *room.coup().turn.value() as usize;
+    send(
+        &mut room,
+        &mut connections,
+        CoupClientEvent::Action {
+            action: PlayerActionType::Exchange,
+        },
+        turn,
+    );
+    let window = room.coup().response_window;
+
+    // Still mid-game as far as the room state goes, only the game's state is gone
+    let event = ServerEvent::CommonEvent(CommonServerEvent::GameChanged {
+        game: GameType::Carbo,
+    });
+    logic::handle_server_event(&mut room, &event, None, true);
+    let sent = connections[0].as_ref().unwrap().0.len();
+
+    logic::handle_response_timeout(&mut room, &mut connections, window);
+    assert_eq!(connections[0].as_ref().unwrap().0.len(), sent);
+}
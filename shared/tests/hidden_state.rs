@@ -0,0 +1,183 @@
+mod common;
+
+use shared::{
+    cards::CardSet,
+    games::{
+        carbo::CarboClientEvent,
+        coup::{CoupClientEvent, CoupSettings, PlayerActionType, Role},
+    },
+    logic::{handle_client_event, room_snapshot, validate_client_event},
+    traits::GameSignal,
+    types::{ClientConnection, ClientEvent, CommonClientEvent, GameType, Room},
+};
+
+fn roles(room: &Room, player_index: usize) -> Vec<Role> {
+    room.players[player_index]
+        .value()
+        .as_ref()
+        .unwrap()
+        .coup()
+        .cards
+        .iter()
+        .map(|card| *card.role.value())
+        .collect()
+}
+
+fn hand(room: &Room, player_index: usize) -> CardSet {
+    *room.players[player_index]
+        .value()
+        .as_ref()
+        .unwrap()
+        .tycoon()
+        .hand
+        .value()
+}
+
+#[test]
+fn tycoon_snapshots_only_show_the_players_own_hand() {
+    let (room, _) = common::started(GameType::Tycoon, 4);
+    let snapshot = room_snapshot(&room, 1);
+
+    assert_eq!(hand(&snapshot, 1), hand(&room, 1));
+    for other in [0, 2, 3] {
+        assert!(hand(&snapshot, other).is_empty());
+
+        // The server's own room keeps everything
+        assert!(!hand(&room, other).is_empty());
+    }
+}
+
+#[test]
+fn coup_snapshots_only_show_the_players_own_roles() {
+    let (room, _) = common::started(GameType::Coup, 4);
+    let snapshot = room_snapshot(&room, 1);
+
+    assert!(snapshot.coup().deck.is_empty());
+    assert_eq!(roles(&snapshot, 1), roles(&room, 1));
+    for other in [0, 2, 3] {
+        assert_eq!(roles(&snapshot, other), [Role::Unknown; 2]);
+    }
+
+    // The server's own room keeps everything
+    assert!(!room.coup().deck.is_empty());
+    assert!(!roles(&room, 0).contains(&Role::Unknown));
+}
+
+#[test]
+fn only_the_exchanging_player_sees_the_drawn_cards() {
+    let (mut room, mut connections) = common::started(GameType::Coup, 4);
+    room.coup_mut().turn.set(0);
+    let ambassador = room.players[0].value_mut().as_mut().unwrap();
+    ambassador.coup_mut().cards[0].role.set(Role::Ambassador);
+
+    // Proving a challenge is enough to let the exchange go ahead
+    let events = [
+        (
+            CoupClientEvent::Action {
+                action: PlayerActionType::Exchange,
+            },
+            0,
+        ),
+        (CoupClientEvent::Challenge, 1),
+        (CoupClientEvent::ResolveChallenge { card: 0 }, 0),
+    ];
+    for (event, player_index) in events {
+        common::send(
+            &mut room,
+            &mut connections,
+            ClientEvent::CoupEvent(event),
+            player_index,
+        );
+    }
+    assert!(!room.coup().exchange_cards.is_empty());
+
+    assert_eq!(
+        room_snapshot(&room, 0).coup().exchange_cards,
+        room.coup().exchange_cards
+    );
+    assert!(room_snapshot(&room, 1).coup().exchange_cards.is_empty());
+}
+
+#[test]
+fn only_the_inquisitor_sees_the_examined_card() {
+    let mut room = common::lobby(GameType::Coup, 4);
+    let mut connections = common::connected(4);
+    room.coup_mut().settings.set(CoupSettings {
+        inquisitor: true,
+        ..Default::default()
+    });
+    common::send(
+        &mut room,
+        &mut connections,
+        ClientEvent::CommonEvent(CommonClientEvent::StartGame),
+        0,
+    );
+    room.coup_mut().turn.set(0);
+    let inquisitor = room.players[0].value_mut().as_mut().unwrap();
+    inquisitor.coup_mut().cards[0].role.set(Role::Inquisitor);
+
+    let events = [
+        (
+            CoupClientEvent::Action {
+                action: PlayerActionType::Examine { target: 1 },
+            },
+            0,
+        ),
+        (CoupClientEvent::Allow, 1),
+        (CoupClientEvent::ShowCard { card: 1 }, 1),
+    ];
+    for (event, player_index) in events {
+        common::send(
+            &mut room,
+            &mut connections,
+            ClientEvent::CoupEvent(event),
+            player_index,
+        );
+    }
+
+    let target = roles(&room, 1);
+    assert_eq!(
+        roles(&room_snapshot(&room, 0), 1),
+        [Role::Unknown, target[1]]
+    );
+    assert_eq!(roles(&room_snapshot(&room, 2), 1), [Role::Unknown; 2]);
+}
+
+#[test]
+fn carbo_snapshots_leave_out_the_draw_pile() {
+    let (room, _) = common::started(GameType::Carbo, 4);
+    let snapshot = room_snapshot(&room, 2);
+
+    assert!(snapshot.carbo().draw_pile.is_empty());
+    for index in 0..4 {
+        let hand = |room: &Room| {
+            *room.players[index]
+                .value()
+                .as_ref()
+                .unwrap()
+                .carbo()
+                .visible_cards
+                .value()
+        };
+        assert_eq!(hand(&snapshot).is_empty(), index != 2);
+        assert!(!hand(&room).is_empty());
+    }
+    assert!(!room.carbo().draw_pile.is_empty());
+}
+
+#[test]
+fn clients_leave_drawing_to_the_server() {
+    let (room, _) = common::started(GameType::Carbo, 4);
+    let turn = *room.carbo().turn.value() as usize;
+    let mut client = room_snapshot(&room, turn);
+    client.carbo_mut().discard_pile = common::cards("3D 4D");
+
+    let draw = ClientEvent::CarboEvent(CarboClientEvent::Draw);
+    assert!(validate_client_event(&client, &draw, turn));
+    handle_client_event(&mut client, &draw, &mut ClientConnection, turn);
+
+    // Nothing is reshuffled or drawn until the server says so
+    assert!(client.carbo().draw_pile.is_empty());
+    assert!(!client.carbo().discard_pile.is_empty());
+    assert_eq!(*client.carbo().turn.value() as usize, turn);
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use shared::{
+    logic::start_game,
+    types::{GameType, ServerEvent},
+};
+
+// The game events sent to each player, so deals can be compared. The seed's commitment is salted with a random
+// nonce so it changes every game
+fn deal(game: GameType, seed: u64) -> Vec<Vec<String>> {
+    let mut room = common::lobby(game, 4);
+    let mut connections = common::connected(4);
+
+    start_game(&mut room, &mut connections, seed);
+    connections
+        .into_iter()
+        .flatten()
+        .map(|recorder| {
+            recorder
+                .0
+                .iter()
+                .filter(|event| !matches!(event, ServerEvent::CommonEvent(_)))
+                .map(|event| format!("{:?}", event))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn the_same_seed_deals_the_same_game() {
+    for game in [GameType::Tycoon, GameType::Carbo, GameType::Coup] {
+        let first = deal(game, 42);
+        assert!(first.iter().all(|events| !events.is_empty()));
+        assert_eq!(first, deal(game, 42), "{:?} deal wasn't reproducible", game);
+        assert_ne!(first, deal(game, 43), "{:?} deal ignored the seed", game);
+    }
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use shared::{
+    games::{
+        carbo::{CarboRoomState, CarboServerEvent},
+        coup::CoupServerEvent,
+        games::game_config,
+        tycoon::TycoonServerEvent,
+    },
+    logic::validate_client_event,
+    traits::GameSignal,
+    types::{ClientEvent, CommonClientEvent, GameType, MAX_PLAYERS, Room, ServerEvent},
+};
+
+fn can_start(room: &Room) -> bool {
+    validate_client_event(
+        room,
+        &ClientEvent::CommonEvent(CommonClientEvent::StartGame),
+        0,
+    )
+}
+
+#[test]
+fn each_game_type_dispatches_to_its_own_logic() {
+    for game_type in GameType::ALL {
+        assert_eq!(game_config(game_type).game_type, game_type);
+
+        let (room, connections) = common::started(game_type, 3);
+        let received = &connections[0].as_ref().unwrap().0;
+        let started = received.iter().any(|event| match (game_type, event) {
+            (GameType::Tycoon, ServerEvent::TycoonEvent(TycoonServerEvent::GameStarted { .. }))
+            | (GameType::Carbo, ServerEvent::CarboEvent(CarboServerEvent::GameStarted { .. }))
+            | (GameType::Coup, ServerEvent::CoupEvent(CoupServerEvent::GameStarted { .. })) => true,
+            (_, ServerEvent::CommonEvent(_)) => false,
+            _ => panic!("{:?} was started with {:?}", game_type, event),
+        });
+        assert!(started, "{:?} never got its start event", game_type);
+
+        match game_type {
+            GameType::Tycoon => assert_eq!(*room.tycoon().round.value(), 1),
+            GameType::Carbo => assert_eq!(*room.carbo().state.value(), CarboRoomState::Game),
+            GameType::Coup => assert!(!room.coup().deck.is_empty()),
+        }
+    }
+
+    // Carbo's own start rules refuse a game that's already running, which coup's wouldn't
+    let mut room = common::lobby(GameType::Carbo, 3);
+    room.carbo_mut().state.set(CarboRoomState::Game);
+    assert!(!(game_config(GameType::Carbo).validate_start_game)(
+        &room, 0
+    ));
+    assert!(!can_start(&room));
+}
+
+#[test]
+fn start_game_enforces_player_counts() {
+    for game_type in GameType::ALL {
+        let config = game_config(game_type);
+        let min_players = config.min_players as usize;
+        let max_players = config.max_players as usize;
+
+        assert!(!can_start(&common::lobby(game_type, min_players - 1)));
+        assert!(can_start(&common::lobby(game_type, min_players)));
+        assert!(can_start(&common::lobby(game_type, max_players)));
+        if max_players < MAX_PLAYERS {
+            assert!(!can_start(&common::lobby(game_type, max_players + 1)));
+        }
+    }
+}
@@ -0,0 +1,502 @@
+mod common;
+
+use common::{Connections, cards};
+use shared::{
+    cards::CardSet,
+    games::tycoon::{
+        MAX_ROUNDS, RoomState, Title, TycoonClientEvent, TycoonRules, TycoonServerEvent,
+    },
+    logic::{handle_client_event, validate_client_event},
+    traits::GameSignal,
+    types::{self, ClientEvent, CommonClientEvent, GameType, Player, Room, ServerEvent},
+};
+
+// A round in progress with player 0 to lead, each player holding the matching hand
+fn dealt(hands: &[&str]) -> Room {
+    let mut room = common::lobby(GameType::Tycoon, hands.len());
+    room.state.set(types::RoomState::InGame);
+    room.tycoon_mut().state.set(RoomState::Game);
+    room.tycoon_mut().round.set(1);
+    for (player, hand) in room.players.iter_mut().zip(hands) {
+        let player = player.value_mut().as_mut().unwrap();
+        player.tycoon_mut().hand.set(cards(hand));
+        player.tycoon_mut().num_cards.set(cards(hand).len() as u8);
+    }
+    room
+}
+
+// Player 0's turn holding `hand`, with `last_played` on top of the trick
+fn playing(hand: &str, last_played: &str) -> Room {
+    let mut room = dealt(&[hand, "", "", ""]);
+    room.tycoon_mut().last_played.set(cards(last_played));
+    room
+}
+
+fn send(
+    room: &mut Room,
+    connections: &mut Connections,
+    event: TycoonClientEvent,
+    player_index: usize,
+) {
+    let event = ClientEvent::TycoonEvent(event);
+    assert!(
+        validate_client_event(room, &event, player_index),
+        "{:?}",
+        event
+    );
+    handle_client_event(room, &event, connections, player_index);
+}
+
+fn play(room: &mut Room, connections: &mut Connections, player_index: usize, played: &str) {
+    let cards = cards(played);
+    send(
+        room,
+        connections,
+        TycoonClientEvent::PlayCards { cards },
+        player_index,
+    );
+}
+
+fn pass(room: &mut Room, connections: &mut Connections, player_index: usize) {
+    send(room, connections, TycoonClientEvent::Pass, player_index);
+}
+
+fn start_game(room: &mut Room, connections: &mut Connections) {
+    let event = ClientEvent::CommonEvent(CommonClientEvent::StartGame);
+    assert!(validate_client_event(room, &event, 0));
+    handle_client_event(room, &event, connections, 0);
+}
+
+fn player(room: &Room, player_index: usize) -> &Player {
+    room.players[player_index].value().as_ref().unwrap()
+}
+
+fn can_play_as(room: &Room, player_index: usize, played: &str) -> bool {
+    let event = ClientEvent::TycoonEvent(TycoonClientEvent::PlayCards {
+        cards: cards(played),
+    });
+    validate_client_event(room, &event, player_index)
+}
+
+fn can_play(room: &Room, played: &str) -> bool {
+    can_play_as(room, 0, played)
+}
+
+fn can_pass(room: &Room, player_index: usize) -> bool {
+    validate_client_event(
+        room,
+        &ClientEvent::TycoonEvent(TycoonClientEvent::Pass),
+        player_index,
+    )
+}
+
+#[test]
+fn only_cards_in_hand_can_be_played() {
+    let room = playing("3H 4D", "");
+    assert!(can_play(&room, "3H"));
+    assert!(!can_play(&room, "3S"));
+    assert!(!can_play(&room, "3H 3S"));
+}
+
+#[test]
+fn jokers_stand_in_for_any_rank_in_a_set() {
+    let room = playing("5H 5S 7D JK JK", "4D 4C 4H");
+    assert!(can_play(&room, "5H 5S JK"));
+    assert!(can_play(&room, "7D JK JK"));
+    assert!(!can_play(&room, "5H 7D JK"));
+}
+
+#[test]
+fn jokers_on_their_own_beat_everything() {
+    let room = playing("2S JK", "2H");
+    assert!(can_play(&room, "JK"));
+
+    let room = playing("2S JK", "JK");
+    assert!(!can_play(&room, "2S"));
+}
+
+#[test]
+fn sequences_are_one_suit_and_jokers_fill_the_gaps() {
+    let room = playing("3H 4H 5H 7H 4S JK", "");
+    assert!(can_play(&room, "3H 4H 5H"));
+    assert!(can_play(&room, "3H 5H JK"));
+    assert!(can_play(&room, "4H 5H JK 7H"));
+    assert!(!can_play(&room, "3H 4S 5H"));
+    assert!(!can_play(&room, "3H 5H 7H JK"));
+
+    // Two cards of different ranks are neither a set nor a sequence
+    assert!(!can_play(&room, "3H 4H"));
+}
+
+#[test]
+fn sequences_beat_lower_sequences_of_the_same_length() {
+    let room = playing("3S 4S 5S 6S 7S", "3H 4H 5H");
+    assert!(can_play(&room, "4S 5S 6S"));
+    assert!(!can_play(&room, "3S 4S 5S"));
+    assert!(!can_play(&room, "4S 5S 6S 7S"));
+}
+
+#[test]
+fn plays_must_match_the_size_and_kind_of_the_trick() {
+    let room = playing("5D 5H 5S 6S 7S", "4D 4C");
+    assert!(can_play(&room, "5H 5S"));
+    assert!(!can_play(&room, "5H"));
+    assert!(!can_play(&room, "5D 5H 5S"));
+
+    let room = playing("5D 5H 5S 6S 7S", "4D 4C 4H");
+    assert!(!can_play(&room, "5S 6S 7S"));
+}
+
+#[test]
+fn revolution_flips_the_ordering() {
+    let mut room = playing("8H TH JK", "9D");
+    assert!(can_play(&room, "TH"));
+    assert!(!can_play(&room, "8H"));
+
+    room.tycoon_mut().revolution.set(true);
+    assert!(can_play(&room, "8H"));
+    assert!(!can_play(&room, "TH"));
+    assert!(can_play(&room, "JK"));
+}
+
+#[test]
+fn passing_needs_a_trick_to_pass_on() {
+    let room = playing("3H", "");
+    assert!(!can_pass(&room, 0));
+
+    let room = playing("3H", "4D");
+    assert!(can_pass(&room, 0));
+    assert!(!can_pass(&room, 1));
+}
+
+#[test]
+fn plays_leave_the_hand_and_move_the_turn_on() {
+    let mut room = dealt(&["3H 4H", "5D 6D", "7C 8C"]);
+    let mut connections = common::connected(3);
+
+    play(&mut room, &mut connections, 0, "3H");
+    assert_eq!(*player(&room, 0).tycoon().hand.value(), cards("4H"));
+    assert_eq!(*player(&room, 0).tycoon().num_cards.value(), 1);
+    assert_eq!(*room.tycoon().last_played.value(), cards("3H"));
+    assert_eq!(room.tycoon().last_played_player, 0);
+    assert_eq!(*room.tycoon().turn.value(), 1);
+}
+
+#[test]
+fn the_trick_clears_once_everyone_else_passes() {
+    let mut room = dealt(&["3H 4H", "5D 6D", "7C 8C"]);
+    let mut connections = common::connected(3);
+
+    play(&mut room, &mut connections, 0, "3H");
+    play(&mut room, &mut connections, 1, "5D");
+    pass(&mut room, &mut connections, 2);
+    assert_eq!(*room.tycoon().last_played.value(), cards("5D"));
+
+    pass(&mut room, &mut connections, 0);
+    assert!(room.tycoon().last_played.value().is_empty());
+    assert_eq!(*room.tycoon().turn.value(), 1);
+}
+
+#[test]
+fn players_who_went_out_are_skipped() {
+    let mut room = dealt(&["3H", "5D 6D", "7C 8C"]);
+    let mut connections = common::connected(3);
+
+    play(&mut room, &mut connections, 0, "3H");
+    assert_eq!(*player(&room, 0).tycoon().finish_position.value(), Some(0));
+    assert!(!can_pass(&room, 0));
+
+    // The trick comes back round to player 0, who is out, so the next player leads
+    pass(&mut room, &mut connections, 1);
+    pass(&mut room, &mut connections, 2);
+    assert!(room.tycoon().last_played.value().is_empty());
+    assert_eq!(*room.tycoon().turn.value(), 1);
+}
+
+#[test]
+fn the_last_round_ends_the_match() {
+    let mut room = dealt(&["3H", "5D", "7C 8C"]);
+    let mut connections = common::connected(3);
+    room.tycoon_mut().rounds.set(1);
+
+    play(&mut room, &mut connections, 0, "3H");
+    play(&mut room, &mut connections, 1, "5D");
+    assert_eq!(*player(&room, 1).tycoon().finish_position.value(), Some(1));
+    assert_eq!(*player(&room, 2).tycoon().finish_position.value(), Some(2));
+
+    // With 3 players the middle one is a Commoner
+    let points: Vec<u16> = (0..3)
+        .map(|index| *player(&room, index).tycoon().points.value())
+        .collect();
+    assert_eq!(points, [4, 2, 0]);
+    assert!(
+        connections[1]
+            .as_ref()
+            .unwrap()
+            .0
+            .iter()
+            .any(|event| matches!(
+                event,
+                ServerEvent::TycoonEvent(TycoonServerEvent::FinalStandings { .. })
+            ))
+    );
+    assert_eq!(*room.tycoon().state.value(), RoomState::Lobby);
+    assert_eq!(*room.state.value(), types::RoomState::Lobby);
+
+    // Starting again is a fresh match
+    start_game(&mut room, &mut connections);
+    assert_eq!(*room.tycoon().round.value(), 1);
+    assert_eq!(*room.tycoon().state.value(), RoomState::Game);
+    for index in 0..3 {
+        assert_eq!(*player(&room, index).tycoon().points.value(), 0);
+        assert_eq!(
+            *player(&room, index).tycoon().title.value(),
+            Title::Commoner
+        );
+    }
+}
+
+#[test]
+fn only_the_host_sets_the_rounds_in_the_lobby() {
+    let set_rounds = |room: &Room, rounds: u8, player_index: usize| {
+        let event = ClientEvent::TycoonEvent(TycoonClientEvent::SetRounds { rounds });
+        validate_client_event(room, &event, player_index)
+    };
+
+    let room = common::lobby(GameType::Tycoon, 2);
+    assert!(set_rounds(&room, 1, 0));
+    assert!(set_rounds(&room, MAX_ROUNDS, 0));
+    assert!(!set_rounds(&room, 0, 0));
+    assert!(!set_rounds(&room, MAX_ROUNDS + 1, 0));
+    assert!(!set_rounds(&room, 2, 1));
+
+    let room = dealt(&["3H", "4H"]);
+    assert!(!set_rounds(&room, 2, 0));
+}
+
+// Players finish the first round in seat order, so player 0 is the Tycoon and player 3 the Beggar
+fn second_round() -> (Room, Connections) {
+    let mut room = dealt(&["3H", "4H", "5H", "6H 7H"]);
+    let mut connections = common::connected(4);
+    play(&mut room, &mut connections, 0, "3H");
+    play(&mut room, &mut connections, 1, "4H");
+    play(&mut room, &mut connections, 2, "5H");
+
+    let titles = [Title::Tycoon, Title::Rich, Title::Poor, Title::Beggar];
+    for (index, title) in titles.iter().enumerate() {
+        assert_eq!(player(&room, index).tycoon().title.value(), title);
+    }
+
+    assert_eq!(*room.tycoon().round.value(), 2);
+    (room, connections)
+}
+
+fn hand_size(room: &Room, player_index: usize) -> u32 {
+    player(room, player_index).tycoon().hand.value().len()
+}
+
+#[test]
+fn the_beggar_and_poor_give_up_their_cards_first() {
+    let (room, _) = second_round();
+    assert_eq!(*room.tycoon().state.value(), RoomState::CardExchange);
+    assert_eq!(*room.tycoon().turn.value(), 3);
+
+    // 54 cards dealt round 4 players is 14, 14, 13, 13 before the exchange
+    assert_eq!(hand_size(&room, 0), 16);
+    assert_eq!(hand_size(&room, 1), 15);
+    assert_eq!(hand_size(&room, 2), 12);
+    assert_eq!(hand_size(&room, 3), 11);
+    assert_eq!(*player(&room, 0).tycoon().cards_to_give.value(), 2);
+    assert_eq!(*player(&room, 1).tycoon().cards_to_give.value(), 1);
+    assert_eq!(*player(&room, 2).tycoon().cards_to_give.value(), 0);
+}
+
+#[test]
+fn play_starts_once_the_tycoon_and_rich_give_cards_back() {
+    let (mut room, mut connections) = second_round();
+    let exchange = |room: &Room, player_index: usize, cards: CardSet| {
+        let event = ClientEvent::TycoonEvent(TycoonClientEvent::ExchangeCards { cards });
+        validate_client_event(room, &event, player_index)
+    };
+    let lowest = |room: &Room, player_index: usize, count: u32| {
+        let hand = *player(room, player_index).tycoon().hand.value();
+        hand.iter().take(count as usize).collect::<CardSet>()
+    };
+
+    let tycoon_cards = lowest(&room, 0, 2);
+    assert!(!exchange(&room, 0, lowest(&room, 0, 1)));
+    assert!(!exchange(&room, 0, lowest(&room, 0, 3)));
+    assert!(!exchange(&room, 0, lowest(&room, 3, 2)));
+    assert!(!exchange(&room, 2, lowest(&room, 2, 1)));
+    assert!(exchange(&room, 0, tycoon_cards));
+
+    let event = TycoonClientEvent::ExchangeCards {
+        cards: tycoon_cards,
+    };
+    send(&mut room, &mut connections, event, 0);
+    assert_eq!(hand_size(&room, 0), 14);
+    assert_eq!(hand_size(&room, 3), 13);
+    assert_eq!(
+        *player(&room, 3).tycoon().hand.value() & tycoon_cards,
+        tycoon_cards
+    );
+    assert_eq!(*room.tycoon().state.value(), RoomState::CardExchange);
+    assert!(!exchange(&room, 0, lowest(&room, 0, 2)));
+
+    let rich_cards = lowest(&room, 1, 1);
+    let event = TycoonClientEvent::ExchangeCards { cards: rich_cards };
+    send(&mut room, &mut connections, event, 1);
+    assert_eq!(hand_size(&room, 1), 14);
+    assert_eq!(hand_size(&room, 2), 13);
+    assert_eq!(*room.tycoon().state.value(), RoomState::Game);
+}
+
+#[test]
+fn eights_clear_the_trick_with_eight_stop() {
+    let mut room = dealt(&["8H 3D", "9D", "TC"]);
+    let mut connections = common::connected(3);
+    play(&mut room, &mut connections, 0, "8H");
+    assert_eq!(*room.tycoon().turn.value(), 1);
+
+    let mut room = dealt(&["8H 3D", "9D", "TC"]);
+    room.tycoon_mut().rules.set(TycoonRules {
+        eight_stop: true,
+        ..Default::default()
+    });
+    play(&mut room, &mut connections, 0, "8H");
+    assert!(room.tycoon().last_played.value().is_empty());
+    assert_eq!(*room.tycoon().turn.value(), 0);
+}
+
+#[test]
+fn jacks_reverse_the_trick_with_eleven_back() {
+    let mut room = dealt(&["JH 4D", "TD QD", "KC"]);
+    let mut connections = common::connected(3);
+    room.tycoon_mut().rules.set(TycoonRules {
+        eleven_back: true,
+        ..Default::default()
+    });
+
+    play(&mut room, &mut connections, 0, "JH");
+    assert!(*room.tycoon().eleven_back.value());
+    assert!(!can_play_as(&room, 1, "QD"));
+    assert!(can_play_as(&room, 1, "TD"));
+
+    // The reversal only lasts until the trick is cleared
+    pass(&mut room, &mut connections, 1);
+    pass(&mut room, &mut connections, 2);
+    assert!(!*room.tycoon().eleven_back.value());
+    assert!(can_play_as(&room, 0, "4D"));
+}
+
+#[test]
+fn the_three_of_spades_beats_a_lone_joker_with_spade_three() {
+    let mut room = dealt(&["JK 4D", "3S 5D", "KC"]);
+    let mut connections = common::connected(3);
+    play(&mut room, &mut connections, 0, "JK");
+    assert!(!can_play_as(&room, 1, "3S"));
+
+    room.tycoon_mut().rules.set(TycoonRules {
+        spade_three: true,
+        ..Default::default()
+    });
+    assert!(can_play_as(&room, 1, "3S"));
+    assert!(!can_play_as(&room, 1, "5D"));
+}
+
+#[test]
+fn the_tycoon_falls_to_beggar_when_beaten_with_capital_fall() {
+    let mut room = dealt(&["3H", "4H 5H", "6H 7H"]);
+    let mut connections = common::connected(3);
+    room.tycoon_mut().rounds.set(1);
+    room.tycoon_mut().rules.set(TycoonRules {
+        capital_fall: true,
+        ..Default::default()
+    });
+    room.players[1]
+        .value_mut()
+        .as_mut()
+        .unwrap()
+        .tycoon_mut()
+        .title
+        .set(Title::Tycoon);
+
+    // Once player 0 goes out first, the old Tycoon is out in last place and only one player is left
+    play(&mut room, &mut connections, 0, "3H");
+    assert_eq!(*player(&room, 1).tycoon().finish_position.value(), Some(2));
+    assert_eq!(*player(&room, 2).tycoon().finish_position.value(), Some(1));
+    assert_eq!(*player(&room, 0).tycoon().title.value(), Title::Tycoon);
+    assert_eq!(*player(&room, 1).tycoon().title.value(), Title::Beggar);
+}
+
+#[test]
+fn sets_of_four_start_and_end_a_revolution() {
+    let mut room = dealt(&["5D 5C 5H 5S 3D", "4D 4C 4H 4S 6D", "KC"]);
+    let mut connections = common::connected(3);
+
+    play(&mut room, &mut connections, 0, "5D 5C 5H 5S");
+    assert!(*room.tycoon().revolution.value());
+    assert!(
+        connections[1]
+            .as_ref()
+            .unwrap()
+            .0
+            .iter()
+            .any(|event| matches!(
+                event,
+                ServerEvent::TycoonEvent(TycoonServerEvent::Revolution { active: true })
+            ))
+    );
+
+    // Lower sets are stronger now, and playing one is a counter-revolution
+    play(&mut room, &mut connections, 1, "4D 4C 4H 4S");
+    assert!(!*room.tycoon().revolution.value());
+}
+
+#[test]
+fn sequences_of_four_only_start_a_revolution_with_the_rule() {
+    let mut room = dealt(&["3H 4H 5H 6H 9D", "KC", "AC"]);
+    let mut connections = common::connected(3);
+    play(&mut room, &mut connections, 0, "3H 4H 5H 6H");
+    assert!(!*room.tycoon().revolution.value());
+
+    let mut room = dealt(&["3H 4H 5H 6H 9D", "KC", "AC"]);
+    room.tycoon_mut().rules.set(TycoonRules {
+        sequence_revolution: true,
+        ..Default::default()
+    });
+    play(&mut room, &mut connections, 0, "3H 4H 5H 6H");
+    assert!(*room.tycoon().revolution.value());
+}
+
+#[test]
+fn the_beggar_gives_jokers_then_the_lowest_cards_during_a_revolution() {
+    let mut room = dealt(&["6H", "5H", "4H", "3H 7H"]);
+    let mut connections = common::connected(4);
+    room.tycoon_mut().revolution.set(true);
+    play(&mut room, &mut connections, 0, "6H");
+    play(&mut room, &mut connections, 1, "5H");
+    play(&mut room, &mut connections, 2, "4H");
+    assert_eq!(*room.tycoon().round.value(), 2);
+
+    let mut dealt_hand = CardSet::EMPTY;
+    let mut given = CardSet::EMPTY;
+    for event in &connections[3].as_ref().unwrap().0 {
+        match event {
+            ServerEvent::TycoonEvent(TycoonServerEvent::GameStarted { cards, .. }) => {
+                dealt_hand = *cards
+            }
+            ServerEvent::TycoonEvent(TycoonServerEvent::GiveCards { cards }) => given = *cards,
+            _ => {}
+        }
+    }
+
+    // Jokers first, then the lowest cards since the ordering is flipped
+    let mut expected = dealt_hand.jokers();
+    expected |= dealt_hand
+        .standard()
+        .iter()
+        .take(2 - expected.len() as usize)
+        .collect();
+    assert_eq!(given, expected);
+}